@@ -4,10 +4,11 @@ use tauri::State;
 use uuid::Uuid;
 
 use crate::{
-    entities::user::{UpdateUser, UserPublic},
+    entities::{
+        pagination::Page,
+        user::{UpdateUser, UserPublic},
+    },
     error::AppError,
-    repositories::supabase::SupabaseRestClient,
-    services::admin_service::AdminService,
     state::AppState,
 };
 
@@ -20,10 +21,8 @@ pub async fn admin_list_users(
     state: State<'_, AppState>,
     limit: u32,
     offset: u32,
-) -> Result<Vec<UserPublic>, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AdminService::new(client);
-    svc.list_users(&state, limit, offset).await.map_err(map_err)
+) -> Result<Page<UserPublic>, String> {
+    state.services.admin.list_users(&state, limit, offset).await.map_err(map_err)
 }
 
 #[tauri::command]
@@ -32,23 +31,33 @@ pub async fn admin_update_user(
     user_id: String,
     patch: UpdateUser,
 ) -> Result<UserPublic, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AdminService::new(client);
     let user_id = Uuid::parse_str(&user_id).map_err(|e| e.to_string())?;
-    svc.update_user(&state, user_id, patch).await.map_err(map_err)
+    state.services.admin.update_user(&state, user_id, patch).await.map_err(map_err)
 }
 
 #[tauri::command]
 pub async fn admin_delete_user(state: State<'_, AppState>, user_id: String) -> Result<(), String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AdminService::new(client);
     let user_id = Uuid::parse_str(&user_id).map_err(|e| e.to_string())?;
-    svc.delete_user(&state, user_id).await.map_err(map_err)
+    state.services.admin.delete_user(&state, user_id).await.map_err(map_err)
 }
 
 #[tauri::command]
-pub async fn admin_user_counts(state: State<'_, AppState>) -> Result<HashMap<String, usize>, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AdminService::new(client);
-    svc.get_counts_by_role(&state).await.map_err(map_err)
+pub async fn admin_user_counts(state: State<'_, AppState>) -> Result<HashMap<String, u64>, String> {
+    state.services.admin.get_counts_by_role(&state).await.map_err(map_err)
+}
+
+/// Re-encrypts every PHI column under `new_secret`. Pass `old_secret` when rotating an
+/// existing key; omit it to backfill PHI fields that currently hold legacy plaintext.
+#[tauri::command]
+pub async fn admin_migrate_phi_encryption(
+    state: State<'_, AppState>,
+    old_secret: Option<String>,
+    new_secret: String,
+) -> Result<usize, String> {
+    state
+        .services
+        .key_rotation
+        .migrate(&state, old_secret.as_deref(), &new_secret)
+        .await
+        .map_err(map_err)
 }
@@ -0,0 +1,90 @@
+use tauri::State;
+
+use crate::{
+    entities::analytics::{AnalyticsFilter, BucketedSummary},
+    error::AppError,
+    ids::PublicId,
+    state::AppState,
+};
+
+fn map_err(e: AppError) -> String {
+    e.to_string()
+}
+
+/// Command-facing analytics filter: `doctor_id` crosses the boundary as an opaque sqid,
+/// like everywhere else, and is decoded here before building the internal `AnalyticsFilter`.
+#[derive(Debug, serde::Deserialize)]
+pub struct AnalyticsFilterRequest {
+    pub date_from: Option<chrono::NaiveDate>,
+    pub date_to: Option<chrono::NaiveDate>,
+    pub doctor_id: Option<String>,
+    pub clinic_id: Option<i32>,
+    pub status: Option<String>,
+}
+
+impl AnalyticsFilterRequest {
+    fn into_filter(self) -> Result<AnalyticsFilter, String> {
+        let doctor_id = self.doctor_id.as_deref().map(PublicId::decode).transpose().map_err(map_err)?;
+        Ok(AnalyticsFilter {
+            date_from: self.date_from,
+            date_to: self.date_to,
+            doctor_id,
+            clinic_id: self.clinic_id,
+            status: self.status,
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn analytics_appointments_by_status(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<BucketedSummary, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.appointments_by_status(&state, filter).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn analytics_appointments_by_day(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<BucketedSummary, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.appointments_by_day(&state, filter).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn analytics_appointments_by_doctor(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<BucketedSummary, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.appointments_by_doctor(&state, filter).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn analytics_most_prescribed_medicines(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<BucketedSummary, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.most_prescribed_medicines(&state, filter).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn analytics_report_volume_by_clinic(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<BucketedSummary, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.report_volume_by_clinic(&state, filter).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn analytics_active_medication_count(
+    state: State<'_, AppState>,
+    filter: AnalyticsFilterRequest,
+) -> Result<usize, String> {
+    let filter = filter.into_filter()?;
+    state.services.analytics.active_medication_count(&state, filter).await.map_err(map_err)
+}
@@ -1,11 +1,6 @@
 use tauri::State;
 
-use crate::{
-    error::AppError,
-    repositories::supabase::SupabaseRestClient,
-    services::auth_service::AuthService,
-    state::AppState,
-};
+use crate::{error::AppError, services::auth_service::AuthService, state::AppState};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct LoginRequest {
@@ -13,6 +8,12 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct LoginVerifyTotpRequest {
+    pub email: String,
+    pub code: String,
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct ForgotPasswordRequest {
     pub email: String,
@@ -25,41 +26,64 @@ pub struct ResetPasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct VerifyTotpRequest {
+    pub code: String,
+}
+
 fn map_err(e: AppError) -> String {
     e.to_string()
 }
 
 #[tauri::command]
-pub async fn auth_login(state: State<'_, AppState>, req: LoginRequest) -> Result<crate::entities::user::UserPublic, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AuthService::new(client);
-    svc.login(&state, &req.email, &req.password).await.map_err(map_err)
+pub async fn auth_login(state: State<'_, AppState>, req: LoginRequest) -> Result<crate::entities::user::LoginOutcome, String> {
+    state.services.auth.login(&state, &req.email, &req.password).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn auth_login_verify_totp(
+    state: State<'_, AppState>,
+    req: LoginVerifyTotpRequest,
+) -> Result<crate::entities::user::UserPublic, String> {
+    state
+        .services
+        .auth
+        .login_verify_totp(&state, &req.email, &req.code)
+        .await
+        .map_err(map_err)
 }
 
 #[tauri::command]
-pub async fn auth_forgot_password(_state: State<'_, AppState>, req: ForgotPasswordRequest) -> Result<(), String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AuthService::new(client);
-    svc.forgot_password(&req.email).await.map_err(map_err)
+pub async fn auth_forgot_password(state: State<'_, AppState>, req: ForgotPasswordRequest) -> Result<(), String> {
+    state.services.auth.forgot_password(&req.email).await.map_err(map_err)
 }
 
 #[tauri::command]
-pub async fn auth_reset_password(_state: State<'_, AppState>, req: ResetPasswordRequest) -> Result<(), String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AuthService::new(client);
-    svc.reset_password(&req.email, &req.code, &req.new_password)
+pub async fn auth_reset_password(state: State<'_, AppState>, req: ResetPasswordRequest) -> Result<(), String> {
+    state
+        .services
+        .auth
+        .reset_password(&req.email, &req.code, &req.new_password)
         .await
         .map_err(map_err)
 }
 
 #[tauri::command]
 pub fn auth_logout(state: State<'_, AppState>) -> Result<(), String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = AuthService::new(client);
-    svc.logout(&state).map_err(map_err)
+    state.services.auth.logout(&state).map_err(map_err)
 }
 
 #[tauri::command]
 pub fn auth_current_user(state: State<'_, AppState>) -> Result<Option<crate::entities::user::UserPublic>, String> {
     AuthService::current_user(&state).map_err(map_err)
 }
+
+#[tauri::command]
+pub async fn auth_enroll_totp(state: State<'_, AppState>) -> Result<String, String> {
+    state.services.auth.enroll_totp(&state).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn auth_verify_totp(state: State<'_, AppState>, req: VerifyTotpRequest) -> Result<bool, String> {
+    state.services.auth.verify_totp(&state, &req.code).await.map_err(map_err)
+}
@@ -1,10 +1,10 @@
 use tauri::State;
 
 use crate::{
-    entities::medication::NewPatientMedication,
+    entities::{medication::NewPatientMedication, pagination::Page, patient::PatientPublic},
     error::AppError,
-    repositories::supabase::SupabaseRestClient,
-    services::{clinic_date, doctor_service::DoctorService},
+    ids::PublicId,
+    services::clinic_date::{self, ClinicSchedule},
     state::AppState,
 };
 
@@ -14,8 +14,8 @@ fn map_err(e: AppError) -> String {
 
 #[derive(Debug, serde::Deserialize)]
 pub struct AddMedicationRequest {
-    pub patient_id: i32,
-    pub doctor_id: i32,
+    pub patient_id: String,
+    pub doctor_id: String,
     pub medicine_name: String,
     pub dosage: String,
     pub frequency: String,
@@ -26,6 +26,11 @@ pub struct AddMedicationRequest {
     pub next_clinic_date: Option<chrono::NaiveDate>,
     pub is_active: bool,
     pub notes: Option<String>,
+    /// The clinic's own recurring schedule (e.g. "2nd Monday, every 2 months"), used to derive
+    /// `next_clinic_date` when the caller doesn't supply one directly. Falls back to
+    /// `ClinicSchedule::DEFAULT` so existing callers that don't know about per-clinic schedules
+    /// keep getting the original "4th Tuesday, monthly" behavior.
+    pub schedule: Option<ClinicSchedule>,
 }
 
 #[tauri::command]
@@ -33,33 +38,33 @@ pub async fn doctor_list_patients(
     state: State<'_, AppState>,
     limit: u32,
     offset: u32,
-) -> Result<Vec<crate::entities::patient::Patient>, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.list_patients(&state, limit, offset).await.map_err(map_err)
+) -> Result<Page<PatientPublic>, String> {
+    state.services.doctor.list_patients(&state, limit, offset).await.map_err(map_err)
 }
 
 #[tauri::command]
 pub async fn doctor_get_patient_overview(
     state: State<'_, AppState>,
-    patient_id: i32,
+    patient_id: String,
 ) -> Result<crate::services::doctor_service::PatientOverview, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.get_patient_overview(&state, patient_id).await.map_err(map_err)
+    let patient_id = PublicId::decode(&patient_id).map_err(map_err)?;
+    state.services.doctor.get_patient_overview(&state, patient_id).await.map_err(map_err)
 }
 
 #[tauri::command]
 pub async fn doctor_list_appointments(
     state: State<'_, AppState>,
-    doctor_id: i32,
+    doctor_id: String,
     limit: u32,
     offset: u32,
-) -> Result<Vec<crate::entities::appointment::Appointment>, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.list_appointments(&state, doctor_id, limit, offset)
+) -> Result<Vec<crate::entities::appointment::AppointmentPublic>, String> {
+    let doctor_id = PublicId::decode(&doctor_id).map_err(map_err)?;
+    state
+        .services
+        .doctor
+        .list_appointments(&state, doctor_id, limit, offset)
         .await
+        .map(|appointments| appointments.into_iter().map(Into::into).collect())
         .map_err(map_err)
 }
 
@@ -67,11 +72,13 @@ pub async fn doctor_list_appointments(
 pub async fn doctor_accept_appointment(
     state: State<'_, AppState>,
     appointment_id: i32,
-) -> Result<crate::entities::appointment::Appointment, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.accept_appointment(&state, appointment_id)
+) -> Result<crate::entities::appointment::AppointmentPublic, String> {
+    state
+        .services
+        .doctor
+        .accept_appointment(&state, appointment_id)
         .await
+        .map(Into::into)
         .map_err(map_err)
 }
 
@@ -80,11 +87,13 @@ pub async fn doctor_reject_appointment(
     state: State<'_, AppState>,
     appointment_id: i32,
     reason: Option<String>,
-) -> Result<crate::entities::appointment::Appointment, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.reject_appointment(&state, appointment_id, reason)
+) -> Result<crate::entities::appointment::AppointmentPublic, String> {
+    state
+        .services
+        .doctor
+        .reject_appointment(&state, appointment_id, reason)
         .await
+        .map(Into::into)
         .map_err(map_err)
 }
 
@@ -92,18 +101,15 @@ pub async fn doctor_reject_appointment(
 pub async fn doctor_add_medication(
     state: State<'_, AppState>,
     req: AddMedicationRequest,
-) -> Result<crate::entities::medication::PatientMedication, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-
+) -> Result<crate::entities::medication::PatientMedicationPublic, String> {
     let next_clinic_date = match req.next_clinic_date {
         Some(d) => d,
-        None => clinic_date::next_default_clinic_date(req.start_date).map_err(map_err)?,
+        None => clinic_date::next_default_clinic_date(req.start_date, req.schedule.unwrap_or(ClinicSchedule::DEFAULT)).map_err(map_err)?,
     };
 
     let new_med = NewPatientMedication {
-        patient_id: req.patient_id,
-        doctor_id: req.doctor_id,
+        patient_id: PublicId::decode(&req.patient_id).map_err(map_err)?,
+        doctor_id: PublicId::decode(&req.doctor_id).map_err(map_err)?,
         medicine_name: req.medicine_name,
         dosage: req.dosage,
         frequency: req.frequency,
@@ -113,27 +119,56 @@ pub async fn doctor_add_medication(
         end_date: req.end_date,
         next_clinic_date,
         is_active: req.is_active,
-        notes: req.notes,
+        notes: req.notes.map(crate::crypto::Encrypted::from),
     };
 
-    svc.add_medication(&state, new_med).await.map_err(map_err)
+    state.services.doctor.add_medication(&state, new_med).await.map(Into::into).map_err(map_err)
 }
 
 #[tauri::command]
 pub async fn doctor_record_patient_visit(
     state: State<'_, AppState>,
-    patient_id: i32,
-    doctor_id: i32,
+    patient_id: String,
+    doctor_id: String,
     notes: Option<String>,
-) -> Result<crate::entities::history::PatientDoctorHistory, String> {
-    let client = SupabaseRestClient::from_env().map_err(map_err)?;
-    let svc = DoctorService::new(client);
-    svc.record_patient_visit(&state, patient_id, doctor_id, notes)
+) -> Result<crate::entities::history::PatientDoctorHistoryPublic, String> {
+    let patient_id = PublicId::decode(&patient_id).map_err(map_err)?;
+    let doctor_id = PublicId::decode(&doctor_id).map_err(map_err)?;
+    state
+        .services
+        .doctor
+        .record_patient_visit(&state, patient_id, doctor_id, notes)
         .await
+        .map(Into::into)
         .map_err(map_err)
 }
 
 #[tauri::command]
-pub fn doctor_default_next_clinic_date(from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, String> {
-    clinic_date::next_default_clinic_date(from_date).map_err(|e| e.to_string())
+pub fn doctor_default_next_clinic_date(
+    from_date: chrono::NaiveDate,
+    schedule: Option<ClinicSchedule>,
+) -> Result<chrono::NaiveDate, String> {
+    clinic_date::next_default_clinic_date(from_date, schedule.unwrap_or(ClinicSchedule::DEFAULT)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn doctor_export_patient_fhir(
+    state: State<'_, AppState>,
+    patient_id: String,
+) -> Result<serde_json::Value, String> {
+    let patient_id = PublicId::decode(&patient_id).map_err(map_err)?;
+    state.services.doctor.export_patient_fhir(&state, patient_id).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn doctor_export_report_printable(
+    state: State<'_, AppState>,
+    appointment_id: i32,
+) -> Result<String, String> {
+    state
+        .services
+        .doctor
+        .export_report_printable(&state, appointment_id)
+        .await
+        .map_err(map_err)
 }
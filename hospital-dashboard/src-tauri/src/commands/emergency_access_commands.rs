@@ -0,0 +1,82 @@
+use tauri::State;
+use uuid::Uuid;
+
+use crate::{entities::emergency_access::EmergencyAccess, error::AppError, ids::PublicId, state::AppState};
+
+fn map_err(e: AppError) -> String {
+    e.to_string()
+}
+
+#[tauri::command]
+pub async fn emergency_access_invite(
+    state: State<'_, AppState>,
+    grantee_user_id: String,
+    patient_id: String,
+    wait_time_days: i32,
+) -> Result<EmergencyAccess, String> {
+    let grantee_user_id = Uuid::parse_str(&grantee_user_id).map_err(|e| e.to_string())?;
+    let patient_id = PublicId::decode(&patient_id).map_err(map_err)?;
+    state
+        .services
+        .emergency_access
+        .invite(&state, grantee_user_id, patient_id, wait_time_days)
+        .await
+        .map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn emergency_access_accept(state: State<'_, AppState>, access_id: i32) -> Result<EmergencyAccess, String> {
+    state.services.emergency_access.accept(&state, access_id).await.map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn emergency_access_initiate_recovery(
+    state: State<'_, AppState>,
+    access_id: i32,
+) -> Result<EmergencyAccess, String> {
+    state
+        .services
+        .emergency_access
+        .initiate_recovery(&state, access_id)
+        .await
+        .map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn emergency_access_approve_recovery(
+    state: State<'_, AppState>,
+    access_id: i32,
+) -> Result<EmergencyAccess, String> {
+    state
+        .services
+        .emergency_access
+        .approve_recovery(&state, access_id)
+        .await
+        .map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn emergency_access_reject_recovery(
+    state: State<'_, AppState>,
+    access_id: i32,
+) -> Result<EmergencyAccess, String> {
+    state
+        .services
+        .emergency_access
+        .reject_recovery(&state, access_id)
+        .await
+        .map_err(map_err)
+}
+
+#[tauri::command]
+pub async fn emergency_access_get_patient_overview(
+    state: State<'_, AppState>,
+    access_id: i32,
+) -> Result<crate::services::doctor_service::PatientOverview, String> {
+    state
+        .services
+        .emergency_access
+        .get_patient_overview_via_emergency(&state, access_id)
+        .await
+        .map_err(map_err)
+}
@@ -0,0 +1,234 @@
+use std::env;
+
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use xsalsa20poly1305::aead::{Aead, KeyInit};
+use xsalsa20poly1305::{Nonce, XSalsa20Poly1305};
+
+use crate::error::{AppError, AppResult};
+
+const NONCE_LEN: usize = 24;
+
+/// Transparent application-layer encryption for a protected health information field.
+/// Serializes as a base64 `nonce||ciphertext` string; deserializes back into `T` by
+/// decrypting first. Repos that read/write PHI (`PatientsRepo`, `HistoryRepo`) get this for
+/// free by typing the field as `Encrypted<T>` instead of `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encrypted<T>(pub T);
+
+impl<T: Serialize> Serialize for Encrypted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let plaintext = serde_json::to_vec(&self.0).map_err(serde::ser::Error::custom)?;
+        let sealed = seal(&plaintext).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&sealed)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Encrypted<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sealed = String::deserialize(deserializer)?;
+
+        if !looks_sealed(&sealed) {
+            // Legacy row written before this field was encrypted: the stored value isn't even
+            // shaped like `base64(nonce||ciphertext)`, so treat it as the plaintext itself. The
+            // next write through `Encrypted<T>`'s `Serialize` impl seals it.
+            let value = serde_json::from_value(serde_json::Value::String(sealed)).map_err(serde::de::Error::custom)?;
+            return Ok(Encrypted(value));
+        }
+
+        // The value is shaped like ciphertext we sealed ourselves, so a decrypt failure here
+        // means a wrong key or a tampered/corrupted value — surface it instead of silently
+        // reinterpreting tampered ciphertext as plaintext.
+        let plaintext = open(&sealed).map_err(serde::de::Error::custom)?;
+        let value = serde_json::from_slice(&plaintext).map_err(serde::de::Error::custom)?;
+        Ok(Encrypted(value))
+    }
+}
+
+/// Whether `sealed` is even shaped like something `seal()` could have produced: valid base64
+/// decoding to at least a nonce's worth of bytes. Used to tell "pre-migration plaintext" (fails
+/// this check) apart from "sealed ciphertext that failed to open" (passes this check but the
+/// decrypt call itself errors), since only the former should fall back to plaintext.
+fn looks_sealed(sealed: &str) -> bool {
+    base64_decode(sealed).is_some_and(|bytes| bytes.len() >= NONCE_LEN)
+}
+
+impl<T> From<T> for Encrypted<T> {
+    fn from(value: T) -> Self {
+        Encrypted(value)
+    }
+}
+
+fn derive_key(secret: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    hasher.finalize().into()
+}
+
+fn active_key() -> AppResult<[u8; 32]> {
+    let secret = env::var("HEALHUB_DATA_KEY").map_err(|_| AppError::MissingEnv("HEALHUB_DATA_KEY".to_string()))?;
+    Ok(derive_key(&secret))
+}
+
+fn seal_with_key(key: &[u8; 32], plaintext: &[u8]) -> AppResult<String> {
+    let cipher = XSalsa20Poly1305::new_from_slice(key).map_err(|e| AppError::Unexpected(e.to_string()))?;
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|e| AppError::Unexpected(e.to_string()))?;
+
+    let mut combined = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    combined.extend_from_slice(&nonce_bytes);
+    combined.extend_from_slice(&ciphertext);
+
+    Ok(base64_encode(&combined))
+}
+
+fn open_with_key(key: &[u8; 32], sealed: &str) -> AppResult<Vec<u8>> {
+    let combined = base64_decode(sealed).ok_or_else(|| AppError::Unexpected("invalid ciphertext encoding".to_string()))?;
+    if combined.len() < NONCE_LEN {
+        return Err(AppError::Unexpected("ciphertext shorter than nonce".to_string()));
+    }
+
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_LEN);
+    let cipher = XSalsa20Poly1305::new_from_slice(key).map_err(|e| AppError::Unexpected(e.to_string()))?;
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| AppError::Unexpected("failed to decrypt field (wrong key or tampered ciphertext)".to_string()))
+}
+
+pub fn seal(plaintext: &[u8]) -> AppResult<String> {
+    seal_with_key(&active_key()?, plaintext)
+}
+
+pub fn open(sealed: &str) -> AppResult<Vec<u8>> {
+    open_with_key(&active_key()?, sealed)
+}
+
+/// Re-encrypts a sealed value under a new `HEALHUB_DATA_KEY`, for key-rotation backfills.
+pub fn rotate(sealed: &str, old_secret: &str, new_secret: &str) -> AppResult<String> {
+    let plaintext = open_with_key(&derive_key(old_secret), sealed)?;
+    seal_with_key(&derive_key(new_secret), &plaintext)
+}
+
+/// Migrates a single column value to ciphertext under `new_secret`. When `old_secret` is
+/// `None` the input is treated as legacy plaintext (first-time backfill); otherwise it is
+/// assumed to already be sealed under `old_secret` (key rotation).
+pub fn migrate_field(raw: &str, old_secret: Option<&str>, new_secret: &str) -> AppResult<String> {
+    let plaintext = match old_secret {
+        Some(old) => open_with_key(&derive_key(old), raw)?,
+        None => serde_json::to_vec(raw)?,
+    };
+    seal_with_key(&derive_key(new_secret), &plaintext)
+}
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let triple = (b0 << 16) | (b1 << 8) | b2;
+
+        out.push(BASE64_ALPHABET[((triple >> 18) & 0x3f) as usize] as char);
+        out.push(BASE64_ALPHABET[((triple >> 12) & 0x3f) as usize] as char);
+        out.push(if chunk.len() > 1 { BASE64_ALPHABET[((triple >> 6) & 0x3f) as usize] as char } else { '=' });
+        out.push(if chunk.len() > 2 { BASE64_ALPHABET[(triple & 0x3f) as usize] as char } else { '=' });
+    }
+    out
+}
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0u32;
+
+    for c in input.trim_end_matches('=').chars() {
+        let value = BASE64_ALPHABET.iter().position(|&b| b as char == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // `active_key()` reads `HEALHUB_DATA_KEY` from the process environment, which is global
+    // state shared across test threads; serialize the tests that touch it so they don't
+    // stomp on each other's key.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_data_key<R>(f: impl FnOnce() -> R) -> R {
+        let _guard = ENV_LOCK.lock().unwrap();
+        env::set_var("HEALHUB_DATA_KEY", "test-only-data-key");
+        let result = f();
+        env::remove_var("HEALHUB_DATA_KEY");
+        result
+    }
+
+    #[test]
+    fn seal_then_open_roundtrips() {
+        with_data_key(|| {
+            let sealed = seal(b"blood type O+").unwrap();
+            assert_eq!(open(&sealed).unwrap(), b"blood type O+");
+        });
+    }
+
+    #[test]
+    fn looks_sealed_rejects_plaintext_that_is_not_shaped_like_ciphertext() {
+        assert!(!looks_sealed("O+"));
+        assert!(!looks_sealed("not even close to base64 ciphertext but has = in it"));
+    }
+
+    #[test]
+    fn looks_sealed_accepts_well_formed_sealed_values() {
+        with_data_key(|| {
+            let sealed = seal(b"O+").unwrap();
+            assert!(looks_sealed(&sealed));
+        });
+    }
+
+    #[test]
+    fn deserialize_falls_back_to_plaintext_for_a_pre_migration_legacy_value() {
+        // A row written before this column was encrypted stores the plaintext directly, which
+        // isn't shaped like `base64(nonce||ciphertext)`.
+        let legacy: Encrypted<String> = serde_json::from_value(serde_json::Value::String("O+".to_string())).unwrap();
+        assert_eq!(legacy.0, "O+");
+    }
+
+    #[test]
+    fn deserialize_errors_instead_of_treating_tampered_ciphertext_as_plaintext() {
+        with_data_key(|| {
+            let sealed = seal(b"O+").unwrap();
+            // Flip the first character of the base64 payload so it still looks sealed (decodes
+            // to at least a nonce's worth of bytes) but no longer opens under the active key.
+            let mut chars: Vec<char> = sealed.chars().collect();
+            chars[0] = if chars[0] == 'A' { 'B' } else { 'A' };
+            let tampered: String = chars.into_iter().collect();
+
+            assert!(looks_sealed(&tampered));
+            let result: Result<Encrypted<String>, _> = serde_json::from_value(serde_json::Value::String(tampered));
+            assert!(result.is_err());
+        });
+    }
+}
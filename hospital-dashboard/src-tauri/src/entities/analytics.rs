@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Reusable filter for analytics aggregates: a date range plus the dimensions callers can
+/// scope down to. Each field is optional and only contributes a filter clause when set.
+/// Not every query honours every field: appointment queries ignore `clinic_id`, medication
+/// queries apply only `doctor_id`, and report queries ignore `status`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalyticsFilter {
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub doctor_id: Option<i32>,
+    pub clinic_id: Option<i32>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bucket {
+    pub bucket: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketedSummary {
+    pub buckets: Vec<Bucket>,
+    pub total: usize,
+}
@@ -1,6 +1,8 @@
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Appointment {
     pub appointment_id: i32,
@@ -21,3 +23,36 @@ pub struct UpdateAppointment {
     pub checked_by_doctor_at: Option<DateTime<Utc>>,
     pub notes: Option<String>,
 }
+
+/// Command-facing view of an `Appointment` with opaque `patient_id`/`doctor_id`, so a patient
+/// overview response can't be used to recover the underlying sequential integer ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentPublic {
+    pub appointment_id: i32,
+    pub patient_id: Option<PublicId>,
+    pub doctor_id: Option<PublicId>,
+    pub appointment_date: NaiveDate,
+    pub appointment_time: NaiveTime,
+    pub status: Option<String>,
+    pub symptoms: Option<String>,
+    pub notes: Option<String>,
+    pub checked_by_doctor_at: Option<DateTime<Utc>>,
+    pub booked_at: Option<DateTime<Utc>>,
+}
+
+impl From<Appointment> for AppointmentPublic {
+    fn from(value: Appointment) -> Self {
+        Self {
+            appointment_id: value.appointment_id,
+            patient_id: value.patient_id.map(PublicId::from),
+            doctor_id: value.doctor_id.map(PublicId::from),
+            appointment_date: value.appointment_date,
+            appointment_time: value.appointment_time,
+            status: value.status,
+            symptoms: value.symptoms,
+            notes: value.notes,
+            checked_by_doctor_at: value.checked_by_doctor_at,
+            booked_at: value.booked_at,
+        }
+    }
+}
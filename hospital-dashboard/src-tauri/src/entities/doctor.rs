@@ -2,6 +2,8 @@ use chrono::{DateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Doctor {
     pub doctor_id: i32,
@@ -33,3 +35,42 @@ pub struct NewDoctor {
     pub end_time: Option<NaiveTime>,
     pub is_available: Option<bool>,
 }
+
+/// Command-facing view of a `Doctor` with an opaque `doctor_id`, so the frontend never
+/// sees the underlying sequential integer primary key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorPublic {
+    pub doctor_id: PublicId,
+    pub user_id: Option<Uuid>,
+    pub full_name: String,
+    pub specialization: String,
+    pub qualification: Option<String>,
+    pub phone: String,
+    pub email: Option<String>,
+    pub consultation_fee: Option<f64>,
+    pub available_days: Option<String>,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub is_available: Option<bool>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<Doctor> for DoctorPublic {
+    fn from(value: Doctor) -> Self {
+        Self {
+            doctor_id: PublicId::from(value.doctor_id),
+            user_id: value.user_id,
+            full_name: value.full_name,
+            specialization: value.specialization,
+            qualification: value.qualification,
+            phone: value.phone,
+            email: value.email,
+            consultation_fee: value.consultation_fee,
+            available_days: value.available_days,
+            start_time: value.start_time,
+            end_time: value.end_time,
+            is_available: value.is_available,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Lifecycle of a break-glass grant: a grantor invites a grantee, the grantee accepts, then
+/// (if the grantor is unreachable) the grantee can start a time-delayed recovery that the
+/// grantor can either approve early, reject outright (permanently blocking the auto-grant),
+/// or leave to expire on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmergencyAccessStatus {
+    Invited,
+    Accepted,
+    RecoveryInitiated,
+    RecoveryApproved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyAccess {
+    pub access_id: i32,
+    pub grantor_user_id: Uuid,
+    pub grantee_user_id: Uuid,
+    pub patient_id: i32,
+    pub status: EmergencyAccessStatus,
+    pub wait_time_days: i32,
+    pub recovery_initiated_at: Option<DateTime<Utc>>,
+    pub last_notification_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewEmergencyAccess {
+    pub grantor_user_id: Uuid,
+    pub grantee_user_id: Uuid,
+    pub patient_id: i32,
+    pub status: EmergencyAccessStatus,
+    pub wait_time_days: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UpdateEmergencyAccess {
+    pub status: Option<EmergencyAccessStatus>,
+    pub recovery_initiated_at: Option<DateTime<Utc>>,
+    pub last_notification_at: Option<DateTime<Utc>>,
+}
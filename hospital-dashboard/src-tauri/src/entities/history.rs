@@ -1,6 +1,9 @@
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::Encrypted;
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatientDoctorHistory {
     pub history_id: i32,
@@ -9,10 +12,39 @@ pub struct PatientDoctorHistory {
     pub encounter_type: String,
     pub encounter_date: NaiveDate,
     pub encounter_time: Option<NaiveTime>,
-    pub notes: Option<String>,
+    pub notes: Option<Encrypted<String>>,
     pub recorded_at: Option<DateTime<Utc>>,
 }
 
+/// Command-facing view of a `PatientDoctorHistory` with opaque `patient_id`/`doctor_id`, so a
+/// patient overview response can't be used to recover the underlying sequential integer ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientDoctorHistoryPublic {
+    pub history_id: i32,
+    pub patient_id: Option<PublicId>,
+    pub doctor_id: Option<PublicId>,
+    pub encounter_type: String,
+    pub encounter_date: NaiveDate,
+    pub encounter_time: Option<NaiveTime>,
+    pub notes: Option<Encrypted<String>>,
+    pub recorded_at: Option<DateTime<Utc>>,
+}
+
+impl From<PatientDoctorHistory> for PatientDoctorHistoryPublic {
+    fn from(value: PatientDoctorHistory) -> Self {
+        Self {
+            history_id: value.history_id,
+            patient_id: value.patient_id.map(PublicId::from),
+            doctor_id: value.doctor_id.map(PublicId::from),
+            encounter_type: value.encounter_type,
+            encounter_date: value.encounter_date,
+            encounter_time: value.encounter_time,
+            notes: value.notes,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewPatientDoctorHistory {
     pub patient_id: i32,
@@ -20,5 +52,5 @@ pub struct NewPatientDoctorHistory {
     pub encounter_type: String,
     pub encounter_date: NaiveDate,
     pub encounter_time: Option<NaiveTime>,
-    pub notes: Option<String>,
+    pub notes: Option<Encrypted<String>>,
 }
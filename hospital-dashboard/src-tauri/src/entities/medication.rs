@@ -2,6 +2,9 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::crypto::Encrypted;
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatientMedication {
     pub medication_id: i32,
@@ -16,10 +19,51 @@ pub struct PatientMedication {
     pub end_date: Option<NaiveDate>,
     pub next_clinic_date: NaiveDate,
     pub is_active: Option<bool>,
-    pub notes: Option<String>,
+    pub notes: Option<Encrypted<String>>,
     pub prescribed_at: Option<DateTime<Utc>>,
 }
 
+/// Command-facing view of a `PatientMedication` with opaque `patient_id`/`doctor_id`, so a
+/// patient overview response can't be used to recover the underlying sequential integer ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientMedicationPublic {
+    pub medication_id: i32,
+    pub patient_id: Option<PublicId>,
+    pub doctor_id: Option<PublicId>,
+    pub medicine_name: String,
+    pub dosage: String,
+    pub frequency: Option<String>,
+    pub times_per_day: Option<i32>,
+    pub specific_times: Option<Value>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_clinic_date: NaiveDate,
+    pub is_active: Option<bool>,
+    pub notes: Option<Encrypted<String>>,
+    pub prescribed_at: Option<DateTime<Utc>>,
+}
+
+impl From<PatientMedication> for PatientMedicationPublic {
+    fn from(value: PatientMedication) -> Self {
+        Self {
+            medication_id: value.medication_id,
+            patient_id: value.patient_id.map(PublicId::from),
+            doctor_id: value.doctor_id.map(PublicId::from),
+            medicine_name: value.medicine_name,
+            dosage: value.dosage,
+            frequency: value.frequency,
+            times_per_day: value.times_per_day,
+            specific_times: value.specific_times,
+            start_date: value.start_date,
+            end_date: value.end_date,
+            next_clinic_date: value.next_clinic_date,
+            is_active: value.is_active,
+            notes: value.notes,
+            prescribed_at: value.prescribed_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewPatientMedication {
     pub patient_id: i32,
@@ -33,5 +77,5 @@ pub struct NewPatientMedication {
     pub end_date: Option<NaiveDate>,
     pub next_clinic_date: NaiveDate,
     pub is_active: bool,
-    pub notes: Option<String>,
+    pub notes: Option<Encrypted<String>>,
 }
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// A page of results alongside the total row count PostgREST reports for the unpaginated
+/// query, so the frontend can render page counts without a second round trip.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: Option<u64>,
+}
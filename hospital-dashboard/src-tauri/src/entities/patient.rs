@@ -2,6 +2,9 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::crypto::Encrypted;
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Patient {
     pub patient_id: i32,
@@ -11,10 +14,10 @@ pub struct Patient {
     pub gender: Option<String>,
     pub phone: String,
     pub address: Option<String>,
-    pub blood_group: Option<String>,
-    pub emergency_contact: Option<String>,
+    pub blood_group: Option<Encrypted<String>>,
+    pub emergency_contact: Option<Encrypted<String>>,
     pub has_chronic_condition: Option<bool>,
-    pub condition_notes: Option<String>,
+    pub condition_notes: Option<Encrypted<String>>,
     pub is_phone_verified: Option<bool>,
     pub created_at: Option<DateTime<Utc>>,
 }
@@ -27,9 +30,48 @@ pub struct NewPatient {
     pub gender: Option<String>,
     pub phone: String,
     pub address: Option<String>,
-    pub blood_group: Option<String>,
-    pub emergency_contact: Option<String>,
+    pub blood_group: Option<Encrypted<String>>,
+    pub emergency_contact: Option<Encrypted<String>>,
     pub has_chronic_condition: Option<bool>,
-    pub condition_notes: Option<String>,
+    pub condition_notes: Option<Encrypted<String>>,
     pub is_phone_verified: Option<bool>,
 }
+
+/// Command-facing view of a `Patient` with an opaque `patient_id`, so the frontend never
+/// sees the underlying sequential integer primary key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientPublic {
+    pub patient_id: PublicId,
+    pub user_id: Option<Uuid>,
+    pub full_name: String,
+    pub dob: NaiveDate,
+    pub gender: Option<String>,
+    pub phone: String,
+    pub address: Option<String>,
+    pub blood_group: Option<Encrypted<String>>,
+    pub emergency_contact: Option<Encrypted<String>>,
+    pub has_chronic_condition: Option<bool>,
+    pub condition_notes: Option<Encrypted<String>>,
+    pub is_phone_verified: Option<bool>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<Patient> for PatientPublic {
+    fn from(value: Patient) -> Self {
+        Self {
+            patient_id: PublicId::from(value.patient_id),
+            user_id: value.user_id,
+            full_name: value.full_name,
+            dob: value.dob,
+            gender: value.gender,
+            phone: value.phone,
+            address: value.address,
+            blood_group: value.blood_group,
+            emergency_contact: value.emergency_contact,
+            has_chronic_condition: value.has_chronic_condition,
+            condition_notes: value.condition_notes,
+            is_phone_verified: value.is_phone_verified,
+            created_at: value.created_at,
+        }
+    }
+}
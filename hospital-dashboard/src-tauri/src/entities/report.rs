@@ -1,14 +1,17 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::Encrypted;
+use crate::ids::PublicId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MedicalReport {
     pub report_id: i32,
     pub appointment_id: Option<i32>,
     pub clinic_id: Option<i32>,
-    pub diagnosis: String,
-    pub prescription: String,
-    pub notes: Option<String>,
+    pub diagnosis: Encrypted<String>,
+    pub prescription: Encrypted<String>,
+    pub notes: Option<Encrypted<String>>,
     pub created_by_doctor_id: Option<i32>,
     pub created_at: Option<DateTime<Utc>>,
 }
@@ -18,7 +21,61 @@ pub struct PrescriptionRecord {
     pub prescription_id: i32,
     pub appointment_id: Option<i32>,
     pub clinic_id: Option<i32>,
-    pub prescription_text: String,
+    pub prescription_text: Encrypted<String>,
     pub prescribed_by_doctor_id: Option<i32>,
     pub created_at: Option<DateTime<Utc>>,
 }
+
+/// Command-facing view of a `MedicalReport` with an opaque `created_by_doctor_id`, so a
+/// patient overview response can't be used to recover the underlying sequential integer id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MedicalReportPublic {
+    pub report_id: i32,
+    pub appointment_id: Option<i32>,
+    pub clinic_id: Option<i32>,
+    pub diagnosis: Encrypted<String>,
+    pub prescription: Encrypted<String>,
+    pub notes: Option<Encrypted<String>>,
+    pub created_by_doctor_id: Option<PublicId>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<MedicalReport> for MedicalReportPublic {
+    fn from(value: MedicalReport) -> Self {
+        Self {
+            report_id: value.report_id,
+            appointment_id: value.appointment_id,
+            clinic_id: value.clinic_id,
+            diagnosis: value.diagnosis,
+            prescription: value.prescription,
+            notes: value.notes,
+            created_by_doctor_id: value.created_by_doctor_id.map(PublicId::from),
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Command-facing view of a `PrescriptionRecord` with an opaque `prescribed_by_doctor_id`, so
+/// a patient overview response can't be used to recover the underlying sequential integer id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrescriptionRecordPublic {
+    pub prescription_id: i32,
+    pub appointment_id: Option<i32>,
+    pub clinic_id: Option<i32>,
+    pub prescription_text: Encrypted<String>,
+    pub prescribed_by_doctor_id: Option<PublicId>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<PrescriptionRecord> for PrescriptionRecordPublic {
+    fn from(value: PrescriptionRecord) -> Self {
+        Self {
+            prescription_id: value.prescription_id,
+            appointment_id: value.appointment_id,
+            clinic_id: value.clinic_id,
+            prescription_text: value.prescription_text,
+            prescribed_by_doctor_id: value.prescribed_by_doctor_id.map(PublicId::from),
+            created_at: value.created_at,
+        }
+    }
+}
@@ -14,6 +14,17 @@ pub struct User {
     pub is_active: Option<bool>,
     pub created_at: Option<DateTime<Utc>>,
     pub auth_user_id: Option<Uuid>,
+    pub totp_secret: Option<String>,
+    pub totp_last_counter: Option<i64>,
+    pub totp_enabled: Option<bool>,
+}
+
+/// Result of `AuthService::login`: either the session was established directly, or the
+/// account has TOTP enabled and a second call to `login_verify_totp` is required to finish.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LoginOutcome {
+    Authenticated(UserPublic),
+    TotpRequired,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,4 +68,7 @@ pub struct UpdateUser {
     pub password_hash: Option<String>,
     pub password_reset_token: Option<String>,
     pub password_reset_expires: Option<String>,
+    pub totp_secret: Option<String>,
+    pub totp_last_counter: Option<i64>,
+    pub totp_enabled: Option<bool>,
 }
@@ -17,6 +17,12 @@ pub enum AppError {
     #[error("serialization error: {0}")]
     Serde(#[from] serde_json::Error),
 
+    #[error("conflict: {0}")]
+    Conflict(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
     #[error("unexpected response: {0}")]
     Unexpected(String),
 }
@@ -0,0 +1,94 @@
+use crate::entities::{
+    doctor::Doctor, medication::PatientMedication, patient::Patient, report::{MedicalReport, PrescriptionRecord},
+};
+
+/// Renders a `MedicalReport` (with its prescriptions and the patient's active medications)
+/// as a self-contained, print-ready HTML document. Tauri's print-to-PDF path renders plain
+/// HTML/CSS, so this builds markup directly rather than going through a template engine.
+pub fn report_printable_html(
+    report: &MedicalReport,
+    prescriptions: &[PrescriptionRecord],
+    medications: &[PatientMedication],
+    patient: &Patient,
+    doctor: Option<&Doctor>,
+) -> String {
+    let doctor_name = doctor.map(|d| d.full_name.as_str()).unwrap_or("Unknown doctor");
+    let doctor_specialization = doctor.map(|d| d.specialization.as_str()).unwrap_or("");
+
+    let prescription_rows: String = prescriptions
+        .iter()
+        .map(|rx| format!("<p>{}</p>", escape_html(&rx.prescription_text.0)))
+        .collect();
+
+    let medication_rows: String = medications
+        .iter()
+        .map(|m| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&m.medicine_name),
+                escape_html(&m.dosage),
+                escape_html(m.frequency.as_deref().unwrap_or("-")),
+                m.next_clinic_date,
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>HealHub Clinic Report</title>
+<style>
+  body {{ font-family: Arial, sans-serif; color: #1a1a1a; margin: 2rem; }}
+  header {{ border-bottom: 2px solid #1a1a1a; padding-bottom: 0.5rem; margin-bottom: 1rem; }}
+  header h1 {{ margin: 0; font-size: 1.4rem; }}
+  .meta {{ display: flex; justify-content: space-between; margin-bottom: 1rem; }}
+  table {{ width: 100%; border-collapse: collapse; margin-top: 0.5rem; }}
+  th, td {{ border: 1px solid #ccc; padding: 0.4rem 0.6rem; text-align: left; font-size: 0.9rem; }}
+  h2 {{ font-size: 1rem; margin-top: 1.5rem; }}
+</style>
+</head>
+<body>
+<header>
+  <h1>HealHub Clinic</h1>
+  <div>Printable patient report</div>
+</header>
+<div class="meta">
+  <div><strong>Patient:</strong> {patient_name}</div>
+  <div><strong>Doctor:</strong> {doctor_name} {doctor_specialization}</div>
+</div>
+<h2>Diagnosis</h2>
+<p>{diagnosis}</p>
+<h2>Prescription</h2>
+<p>{prescription}</p>
+{prescription_rows}
+<h2>Active Medications</h2>
+<table>
+  <thead><tr><th>Medicine</th><th>Dosage</th><th>Frequency</th><th>Next clinic date</th></tr></thead>
+  <tbody>{medication_rows}</tbody>
+</table>
+{notes}
+</body>
+</html>"#,
+        patient_name = escape_html(&patient.full_name),
+        doctor_name = escape_html(doctor_name),
+        doctor_specialization = escape_html(doctor_specialization),
+        diagnosis = escape_html(&report.diagnosis.0),
+        prescription = escape_html(&report.prescription.0),
+        prescription_rows = prescription_rows,
+        medication_rows = medication_rows,
+        notes = report
+            .notes
+            .as_ref()
+            .map(|n| format!("<h2>Notes</h2><p>{}</p>", escape_html(&n.0)))
+            .unwrap_or_default(),
+    )
+}
+
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,265 @@
+use serde_json::{json, Value};
+
+use crate::{
+    entities::{
+        appointment::AppointmentPublic,
+        doctor::Doctor,
+        history::PatientDoctorHistoryPublic,
+        medication::{PatientMedication, PatientMedicationPublic},
+        patient::Patient,
+        report::{MedicalReportPublic, PrescriptionRecordPublic},
+    },
+    ids::PublicId,
+};
+
+/// Maps HealHub entities into FHIR R4 resources (built directly as JSON, since we only need
+/// to emit conformant resources, not parse or round-trip arbitrary FHIR). Resource ids reuse
+/// the opaque `PublicId` sqid so exported records don't leak HealHub's internal row counts.
+
+pub fn patient_resource(patient: &Patient) -> Value {
+    let id = PublicId::encode(patient.patient_id);
+    json!({
+        "resourceType": "Patient",
+        "id": id.clone(),
+        "identifier": [{ "system": "urn:healhub:patient-id", "value": id }],
+        "name": [{ "text": patient.full_name }],
+        "gender": patient.gender,
+        "birthDate": patient.dob,
+        "telecom": [{ "system": "phone", "value": patient.phone }],
+        "address": patient.address.as_ref().map(|line| json!([{ "text": line }])),
+    })
+}
+
+pub fn practitioner_resource(doctor: &Doctor) -> Value {
+    let id = PublicId::encode(doctor.doctor_id);
+    json!({
+        "resourceType": "Practitioner",
+        "id": id,
+        "name": [{ "text": doctor.full_name }],
+        "qualification": doctor.qualification.as_ref().map(|q| json!([{ "code": { "text": q } }])),
+        "telecom": [{ "system": "phone", "value": doctor.phone }],
+    })
+}
+
+pub fn encounter_resource(appointment: &AppointmentPublic) -> Value {
+    let id = appointment.appointment_id.to_string();
+    json!({
+        "resourceType": "Encounter",
+        "id": id,
+        "status": encounter_status(appointment.status.as_deref()),
+        "subject": appointment.patient_id.map(|id| reference("Patient", PublicId::encode(id.0))),
+        "participant": appointment.doctor_id.map(|id| json!([{
+            "individual": reference("Practitioner", PublicId::encode(id.0)),
+        }])),
+        "period": { "start": format!("{}T{}", appointment.appointment_date, appointment.appointment_time) },
+        "reasonCode": appointment.symptoms.as_ref().map(|s| json!([{ "text": s }])),
+    })
+}
+
+fn encounter_status(status: Option<&str>) -> &'static str {
+    match status {
+        Some("Confirmed") => "finished",
+        Some("Cancelled") => "cancelled",
+        _ => "planned",
+    }
+}
+
+/// A `PatientDoctorHistory` row is always a past visit, so (unlike `Appointment`, which can
+/// still be pending) it maps to an `Encounter` that already happened.
+pub fn encounter_from_history(history: &PatientDoctorHistoryPublic) -> Value {
+    let id = format!("history-{}", history.history_id);
+    json!({
+        "resourceType": "Encounter",
+        "id": id,
+        "status": "finished",
+        "class": { "code": history.encounter_type },
+        "subject": history.patient_id.map(|id| reference("Patient", PublicId::encode(id.0))),
+        "participant": history.doctor_id.map(|id| json!([{
+            "individual": reference("Practitioner", PublicId::encode(id.0)),
+        }])),
+        "period": { "start": match history.encounter_time {
+            Some(time) => format!("{}T{}", history.encounter_date, time),
+            None => history.encounter_date.to_string(),
+        } },
+        "reasonCode": history.notes.as_ref().map(|n| json!([{ "text": n.0.clone() }])),
+    })
+}
+
+pub fn diagnostic_report_resource(report: &MedicalReportPublic, encounter_id: Option<&str>) -> Value {
+    json!({
+        "resourceType": "DiagnosticReport",
+        "id": report.report_id.to_string(),
+        "status": "final",
+        "code": { "text": report.diagnosis.0.clone() },
+        "encounter": encounter_id.map(|id| reference("Encounter", id.to_string())),
+        "conclusion": report.notes.as_ref().map(|n| n.0.clone()),
+        "effectiveDateTime": report.created_at,
+    })
+}
+
+pub fn condition_resource(report: &MedicalReportPublic, patient_id: Option<i32>) -> Value {
+    json!({
+        "resourceType": "Condition",
+        "id": format!("condition-{}", report.report_id),
+        "code": { "text": report.diagnosis.0.clone() },
+        "subject": patient_id.map(|id| reference("Patient", PublicId::encode(id))),
+        "note": report.notes.as_ref().map(|n| json!([{ "text": n.0.clone() }])),
+        "recordedDate": report.created_at,
+    })
+}
+
+pub fn medication_statement_resource(med: &PatientMedicationPublic) -> Value {
+    let timing = json!({
+        "repeat": {
+            "frequency": med.times_per_day,
+            "period": 1,
+            "periodUnit": "d",
+            "timeOfDay": med.specific_times,
+        }
+    });
+
+    json!({
+        "resourceType": "MedicationStatement",
+        "id": format!("medication-{}", med.medication_id),
+        "status": if med.is_active.unwrap_or(false) { "active" } else { "completed" },
+        "medicationCodeableConcept": { "text": med.medicine_name },
+        "subject": med.patient_id.map(|id| reference("Patient", PublicId::encode(id.0))),
+        "informationSource": med.doctor_id.map(|id| reference("Practitioner", PublicId::encode(id.0))),
+        "effectivePeriod": {
+            "start": med.start_date,
+            "end": med.end_date,
+        },
+        "dosage": [{
+            "text": med.dosage,
+            "timing": timing,
+        }],
+        "note": med.notes.as_ref().map(|n| json!([{ "text": n.0.clone() }])),
+    })
+}
+
+pub fn medication_request_from_prescription(rx: &PrescriptionRecordPublic) -> Value {
+    json!({
+        "resourceType": "MedicationRequest",
+        "id": format!("prescription-{}", rx.prescription_id),
+        "status": "active",
+        "intent": "order",
+        "medicationCodeableConcept": { "text": rx.prescription_text.0.clone() },
+        "requester": rx.prescribed_by_doctor_id.map(|id| reference("Practitioner", PublicId::encode(id.0))),
+    })
+}
+
+/// A `next_clinic_date` follow-up, represented as a `ServiceRequest` for a future encounter.
+pub fn followup_service_request(med: &PatientMedication) -> Value {
+    json!({
+        "resourceType": "ServiceRequest",
+        "id": format!("followup-{}", med.medication_id),
+        "status": "active",
+        "intent": "plan",
+        "code": { "text": "Follow-up clinic visit" },
+        "subject": med.patient_id.map(|id| reference("Patient", PublicId::encode(id))),
+        "occurrenceDateTime": med.next_clinic_date,
+    })
+}
+
+fn reference(resource_type: &str, id: String) -> Value {
+    json!({ "reference": format!("{}/{}", resource_type, id) })
+}
+
+/// Bundles every exported resource into a FHIR `Bundle` of type `collection`.
+pub fn bundle(resources: Vec<Value>) -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": resources.into_iter().map(|resource| json!({ "resource": resource })).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::crypto::Encrypted;
+
+    fn sample_patient() -> Patient {
+        Patient {
+            patient_id: 42,
+            user_id: None,
+            full_name: "Jane Doe".to_string(),
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+            gender: Some("F".to_string()),
+            phone: "0771234567".to_string(),
+            address: None,
+            blood_group: None,
+            emergency_contact: None,
+            has_chronic_condition: None,
+            condition_notes: None,
+            is_phone_verified: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn patient_resource_uses_the_opaque_public_id() {
+        let resource = patient_resource(&sample_patient());
+        assert_eq!(resource["resourceType"], "Patient");
+        assert_eq!(resource["id"], PublicId::encode(42));
+    }
+
+    #[test]
+    fn encounter_from_history_references_patient_and_practitioner_by_public_id() {
+        let history = PatientDoctorHistoryPublic {
+            history_id: 7,
+            patient_id: Some(PublicId::from(42)),
+            doctor_id: Some(PublicId::from(9)),
+            encounter_type: "Consultation".to_string(),
+            encounter_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            encounter_time: None,
+            notes: Some(Encrypted::from("stable".to_string())),
+            recorded_at: None,
+        };
+
+        let resource = encounter_from_history(&history);
+        assert_eq!(resource["resourceType"], "Encounter");
+        assert_eq!(resource["id"], "history-7");
+        assert_eq!(resource["subject"]["reference"], format!("Patient/{}", PublicId::encode(42)));
+        assert_eq!(
+            resource["participant"][0]["individual"]["reference"],
+            format!("Practitioner/{}", PublicId::encode(9))
+        );
+    }
+
+    #[test]
+    fn medication_statement_resource_reflects_active_status_and_public_ids() {
+        let med = PatientMedicationPublic {
+            medication_id: 3,
+            patient_id: Some(PublicId::from(42)),
+            doctor_id: Some(PublicId::from(9)),
+            medicine_name: "Metformin".to_string(),
+            dosage: "500mg".to_string(),
+            frequency: Some("Daily".to_string()),
+            times_per_day: Some(2),
+            specific_times: None,
+            start_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            end_date: None,
+            next_clinic_date: NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+            is_active: Some(true),
+            notes: None,
+            prescribed_at: None,
+        };
+
+        let resource = medication_statement_resource(&med);
+        assert_eq!(resource["resourceType"], "MedicationStatement");
+        assert_eq!(resource["status"], "active");
+        assert_eq!(resource["subject"]["reference"], format!("Patient/{}", PublicId::encode(42)));
+    }
+
+    #[test]
+    fn bundle_wraps_each_resource_in_an_entry() {
+        let resources = vec![patient_resource(&sample_patient())];
+        let result = bundle(resources);
+        assert_eq!(result["resourceType"], "Bundle");
+        assert_eq!(result["type"], "collection");
+        assert_eq!(result["entry"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,63 @@
+use std::env;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqids::Sqids;
+
+use crate::error::{AppError, AppResult};
+
+/// Opaque public identifier for a sequential integer primary key (patients, doctors), so ids
+/// crossing the command boundary don't leak row counts or allow enumeration. Encodes as a
+/// short string via `sqids`; repos keep working with the underlying `i32` for PostgREST
+/// filters, converting at the command boundary only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicId(pub i32);
+
+fn codec() -> &'static Sqids {
+    static CODEC: OnceLock<Sqids> = OnceLock::new();
+    CODEC.get_or_init(|| {
+        let mut options = sqids::Options::default();
+        if let Ok(alphabet) = env::var("SQIDS_ALPHABET") {
+            options.alphabet = alphabet.chars().collect();
+        }
+        if let Ok(min_length) = env::var("SQIDS_MIN_LENGTH") {
+            if let Ok(min_length) = min_length.parse::<u8>() {
+                options.min_length = min_length;
+            }
+        }
+        Sqids::new(Some(options)).expect("invalid SQIDS_ALPHABET/SQIDS_MIN_LENGTH configuration")
+    })
+}
+
+impl PublicId {
+    pub fn encode(id: i32) -> String {
+        codec().encode(&[id as u64]).unwrap_or_default()
+    }
+
+    pub fn decode(value: &str) -> AppResult<i32> {
+        let numbers = codec().decode(value);
+        match numbers.as_slice() {
+            [n] if *n <= i32::MAX as u64 => Ok(*n as i32),
+            _ => Err(AppError::Validation(format!("invalid id: {}", value))),
+        }
+    }
+}
+
+impl From<i32> for PublicId {
+    fn from(id: i32) -> Self {
+        PublicId(id)
+    }
+}
+
+impl Serialize for PublicId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Self::encode(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::decode(&raw).map(PublicId).map_err(serde::de::Error::custom)
+    }
+}
@@ -1,25 +1,35 @@
 pub mod commands;
+pub mod crypto;
 pub mod entities;
 pub mod error;
+pub mod export;
+pub mod fhir;
+pub mod ids;
 pub mod repositories;
 pub mod services;
 pub mod state;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let app_state = state::AppState::new().expect("failed to initialize application state");
+
     tauri::Builder::default()
-        .manage(state::AppState::default())
+        .manage(app_state)
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             commands::auth_commands::auth_login,
+            commands::auth_commands::auth_login_verify_totp,
             commands::auth_commands::auth_forgot_password,
             commands::auth_commands::auth_reset_password,
             commands::auth_commands::auth_logout,
             commands::auth_commands::auth_current_user,
+            commands::auth_commands::auth_enroll_totp,
+            commands::auth_commands::auth_verify_totp,
             commands::admin_commands::admin_list_users,
             commands::admin_commands::admin_update_user,
             commands::admin_commands::admin_delete_user,
             commands::admin_commands::admin_user_counts,
+            commands::admin_commands::admin_migrate_phi_encryption,
             commands::doctor_commands::doctor_list_patients,
             commands::doctor_commands::doctor_get_patient_overview,
             commands::doctor_commands::doctor_list_appointments,
@@ -28,6 +38,20 @@ pub fn run() {
             commands::doctor_commands::doctor_add_medication,
             commands::doctor_commands::doctor_record_patient_visit,
             commands::doctor_commands::doctor_default_next_clinic_date,
+            commands::doctor_commands::doctor_export_patient_fhir,
+            commands::doctor_commands::doctor_export_report_printable,
+            commands::analytics_commands::analytics_appointments_by_status,
+            commands::analytics_commands::analytics_appointments_by_day,
+            commands::analytics_commands::analytics_appointments_by_doctor,
+            commands::analytics_commands::analytics_most_prescribed_medicines,
+            commands::analytics_commands::analytics_report_volume_by_clinic,
+            commands::analytics_commands::analytics_active_medication_count,
+            commands::emergency_access_commands::emergency_access_invite,
+            commands::emergency_access_commands::emergency_access_accept,
+            commands::emergency_access_commands::emergency_access_initiate_recovery,
+            commands::emergency_access_commands::emergency_access_approve_recovery,
+            commands::emergency_access_commands::emergency_access_reject_recovery,
+            commands::emergency_access_commands::emergency_access_get_patient_overview,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,63 @@
+use crate::{
+    entities::{
+        analytics::AnalyticsFilter,
+        appointment::Appointment,
+        medication::PatientMedication,
+        report::MedicalReport,
+    },
+    error::AppResult,
+    repositories::{query::Query, supabase::SupabaseRestClient},
+};
+
+#[derive(Clone)]
+pub struct AnalyticsRepo {
+    client: SupabaseRestClient,
+}
+
+impl AnalyticsRepo {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self { client }
+    }
+
+    pub async fn appointments(&self, filter: &AnalyticsFilter) -> AppResult<Vec<Appointment>> {
+        let mut query = Query::new();
+        if let Some(from) = filter.date_from {
+            query = query.gte("appointment_date", from);
+        }
+        if let Some(to) = filter.date_to {
+            query = query.lte("appointment_date", to);
+        }
+        if let Some(doctor_id) = filter.doctor_id {
+            query = query.eq("doctor_id", doctor_id);
+        }
+        if let Some(status) = &filter.status {
+            query = query.eq("status", status);
+        }
+        self.client.select::<Appointment>("appointments", query).await
+    }
+
+    pub async fn active_medications(&self, filter: &AnalyticsFilter) -> AppResult<Vec<PatientMedication>> {
+        let mut query = Query::new().eq("is_active", true);
+        if let Some(doctor_id) = filter.doctor_id {
+            query = query.eq("doctor_id", doctor_id);
+        }
+        self.client.select::<PatientMedication>("patient_medications", query).await
+    }
+
+    pub async fn medical_reports(&self, filter: &AnalyticsFilter) -> AppResult<Vec<MedicalReport>> {
+        let mut query = Query::new();
+        if let Some(from) = filter.date_from {
+            query = query.gte("created_at", from);
+        }
+        if let Some(to) = filter.date_to {
+            query = query.lte("created_at", to);
+        }
+        if let Some(doctor_id) = filter.doctor_id {
+            query = query.eq("created_by_doctor_id", doctor_id);
+        }
+        if let Some(clinic_id) = filter.clinic_id {
+            query = query.eq("clinic_id", clinic_id);
+        }
+        self.client.select::<MedicalReport>("medical_reports", query).await
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
     entities::appointment::{Appointment, UpdateAppointment},
     error::AppResult,
-    repositories::supabase::SupabaseRestClient,
+    repositories::{
+        query::{Order, Query},
+        supabase::SupabaseRestClient,
+    },
 };
 
 #[derive(Clone)]
@@ -14,39 +17,34 @@ impl AppointmentsRepo {
         Self { client }
     }
 
+    pub async fn get_by_id(&self, appointment_id: i32) -> AppResult<Option<Appointment>> {
+        let query = Query::new().eq("appointment_id", appointment_id);
+        let rows = self.client.select::<Appointment>("appointments", query).await?;
+        Ok(rows.into_iter().next())
+    }
+
     pub async fn list_for_doctor(&self, doctor_id: i32, limit: u32, offset: u32) -> AppResult<Vec<Appointment>> {
-        self.client
-            .select::<Appointment>(
-                "appointments",
-                &format!(
-                    "doctor_id=eq.{}&order=appointment_date.desc,appointment_time.desc&limit={}&offset={}",
-                    doctor_id, limit, offset
-                ),
-            )
-            .await
+        let query = Query::new()
+            .eq("doctor_id", doctor_id)
+            .order("appointment_date", Order::Desc)
+            .order("appointment_time", Order::Desc)
+            .limit(limit)
+            .offset(offset);
+        self.client.select::<Appointment>("appointments", query).await
     }
 
     pub async fn list_for_patient(&self, patient_id: i32, limit: u32) -> AppResult<Vec<Appointment>> {
-        self.client
-            .select::<Appointment>(
-                "appointments",
-                &format!(
-                    "patient_id=eq.{}&order=appointment_date.desc,appointment_time.desc&limit={}",
-                    patient_id, limit
-                ),
-            )
-            .await
+        let query = Query::new()
+            .eq("patient_id", patient_id)
+            .order("appointment_date", Order::Desc)
+            .order("appointment_time", Order::Desc)
+            .limit(limit);
+        self.client.select::<Appointment>("appointments", query).await
     }
 
     pub async fn update(&self, appointment_id: i32, patch: &UpdateAppointment) -> AppResult<Appointment> {
-        let rows = self
-            .client
-            .update::<Appointment, _>(
-                "appointments",
-                &format!("appointment_id=eq.{}", appointment_id),
-                patch,
-            )
-            .await?;
+        let query = Query::new().eq("appointment_id", appointment_id);
+        let rows = self.client.update::<Appointment, _>("appointments", query, patch).await?;
         Ok(rows
             .into_iter()
             .next()
@@ -0,0 +1,55 @@
+use crate::{
+    entities::authz::{Role, RolePermissionRow, RoleRow},
+    error::AppResult,
+    repositories::supabase::SupabaseRestClient,
+};
+
+#[derive(Clone)]
+pub struct AuthzRepo {
+    client: SupabaseRestClient,
+}
+
+impl AuthzRepo {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self { client }
+    }
+
+    pub async fn get_role_by_name(&self, name: &str) -> AppResult<Option<Role>> {
+        let name_enc = urlencoding::encode(name);
+        let role_row = self
+            .client
+            .select::<RoleRow>("roles", &format!("name=eq.{}&limit=1", name_enc))
+            .await?
+            .into_iter()
+            .next();
+
+        let role_row = match role_row {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        let permission_rows = self
+            .client
+            .select::<RolePermissionRow>(
+                "role_permissions",
+                &format!("role_id=eq.{}", role_row.role_id),
+            )
+            .await?;
+
+        Ok(Some(Role {
+            name: role_row.name,
+            permissions: permission_rows
+                .into_iter()
+                .map(|row| crate::entities::authz::Permission {
+                    resource: row.resource,
+                    action: row.action,
+                })
+                .collect(),
+        }))
+    }
+
+    pub async fn list_role_names(&self) -> AppResult<Vec<String>> {
+        let rows = self.client.select::<RoleRow>("roles", "select=role_id,name").await?;
+        Ok(rows.into_iter().map(|r| r.name).collect())
+    }
+}
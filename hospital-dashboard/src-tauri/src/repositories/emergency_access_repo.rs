@@ -0,0 +1,42 @@
+use crate::{
+    entities::emergency_access::{EmergencyAccess, NewEmergencyAccess, UpdateEmergencyAccess},
+    error::AppResult,
+    repositories::{query::Query, supabase::SupabaseRestClient},
+};
+
+#[derive(Clone)]
+pub struct EmergencyAccessRepo {
+    client: SupabaseRestClient,
+}
+
+impl EmergencyAccessRepo {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self { client }
+    }
+
+    pub async fn insert(&self, new_access: &NewEmergencyAccess) -> AppResult<EmergencyAccess> {
+        let rows = self
+            .client
+            .insert::<EmergencyAccess, _>("emergency_access", &vec![new_access])
+            .await?;
+        Ok(rows.into_iter().next().expect("no inserted row"))
+    }
+
+    pub async fn get_by_id(&self, access_id: i32) -> AppResult<Option<EmergencyAccess>> {
+        let query = Query::new().eq("access_id", access_id);
+        let rows = self.client.select::<EmergencyAccess>("emergency_access", query).await?;
+        Ok(rows.into_iter().next())
+    }
+
+    pub async fn update(&self, access_id: i32, patch: &UpdateEmergencyAccess) -> AppResult<EmergencyAccess> {
+        let query = Query::new().eq("access_id", access_id);
+        let rows = self
+            .client
+            .update::<EmergencyAccess, _>("emergency_access", query, patch)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .next()
+            .expect("supabase returned no updated row"))
+    }
+}
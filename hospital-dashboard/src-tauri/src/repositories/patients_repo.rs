@@ -23,6 +23,17 @@ impl PatientsRepo {
             .await
     }
 
+    /// Like `list`, but also returns the total row count reported by PostgREST, for UI
+    /// pagination that needs a total page count without downloading every row.
+    pub async fn list_with_count(&self, limit: u32, offset: u32) -> AppResult<(Vec<Patient>, Option<u64>)> {
+        self.client
+            .select_with_count::<Patient>(
+                "patients",
+                &format!("select=*&order=created_at.desc&limit={}&offset={}", limit, offset),
+            )
+            .await
+    }
+
     pub async fn get_by_id(&self, patient_id: i32) -> AppResult<Option<Patient>> {
         let rows = self
             .client
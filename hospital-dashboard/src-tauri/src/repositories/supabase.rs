@@ -1,49 +1,188 @@
 use std::env;
+use std::sync::{Arc, Mutex};
 
+use chrono::Utc;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::{AppError, AppResult};
+use crate::repositories::query::QuerySpec;
+
+/// How close to expiry (in seconds) we proactively refresh the access token.
+const REFRESH_SKEW_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone)]
+struct UserSession {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: i64,
+}
 
 #[derive(Clone)]
 pub struct SupabaseRestClient {
     base_url: String,
     api_key: String,
     http: reqwest::Client,
+    user_session: Option<Arc<Mutex<UserSession>>>,
 }
 
 impl SupabaseRestClient {
-    pub fn from_env() -> AppResult<Self> {
+    /// Builds a client scoped to the anon key, reusing `http` (the shared keep-alive
+    /// connection pool every `SupabaseRestClient` in `AppState` is built from) rather than
+    /// opening a pool of its own. Per-command callers that have an authenticated user should
+    /// layer `with_user_token` on top so PostgREST RLS applies per user.
+    pub fn from_env(http: reqwest::Client) -> AppResult<Self> {
         let base_url = env::var("SUPABASE_URL")
             .map_err(|_| AppError::MissingEnv("SUPABASE_URL".to_string()))?;
 
-        // For now, we prefer service role key because admin/doctor dashboard needs elevated access.
-        // You can also set SUPABASE_ANON_KEY and tighten RLS later.
-        let api_key = env::var("SUPABASE_SERVICE_ROLE_KEY")
+        let api_key = env::var("SUPABASE_ANON_KEY")
             .or_else(|_| env::var("SUPABASE_KEY"))
-            .or_else(|_| env::var("SUPABASE_ANON_KEY"))
-            .map_err(|_| AppError::MissingEnv("SUPABASE_SERVICE_ROLE_KEY (or SUPABASE_KEY/SUPABASE_ANON_KEY)".to_string()))?;
+            .map_err(|_| AppError::MissingEnv("SUPABASE_ANON_KEY (or SUPABASE_KEY)".to_string()))?;
 
-        let http = reqwest::Client::builder().build()?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            http,
+            user_session: None,
+        })
+    }
+
+    /// Builds a client that always authenticates with the service-role key, bypassing RLS.
+    /// Use only for operations that are genuinely admin-scoped (see `AdminService`). Reuses
+    /// `http`, same as `from_env`.
+    pub fn admin_from_env(http: reqwest::Client) -> AppResult<Self> {
+        let base_url = env::var("SUPABASE_URL")
+            .map_err(|_| AppError::MissingEnv("SUPABASE_URL".to_string()))?;
+        let api_key = env::var("SUPABASE_SERVICE_ROLE_KEY")
+            .map_err(|_| AppError::MissingEnv("SUPABASE_SERVICE_ROLE_KEY".to_string()))?;
 
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             api_key,
             http,
+            user_session: None,
         })
     }
 
+    /// Returns a clone of this client that authenticates requests with a per-user JWT
+    /// (the `access_token` from a GoTrue password grant) instead of the shared api key,
+    /// so PostgREST row-level security is evaluated as that user.
+    pub fn with_user_token(&self, access_token: String, refresh_token: Option<String>) -> Self {
+        let expires_at = decode_jwt_exp(&access_token).unwrap_or(0);
+        let mut clone = self.clone();
+        clone.user_session = Some(Arc::new(Mutex::new(UserSession {
+            access_token,
+            refresh_token,
+            expires_at,
+        })));
+        clone
+    }
+
+    /// Exchanges an email/password pair for a GoTrue user session via the password grant.
+    pub async fn exchange_password_for_token(&self, email: &str, password: &str) -> AppResult<(String, Option<String>)> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            refresh_token: Option<String>,
+        }
+
+        let url = format!("{}/auth/v1/token?grant_type=password", self.base_url);
+        let resp = self
+            .http
+            .post(url)
+            .header("apikey", &self.api_key)
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(AppError::Unexpected(format!(
+                "supabase auth token exchange failed: {} {}",
+                status, body
+            )));
+        }
+
+        let parsed: TokenResponse = resp.json().await?;
+        Ok((parsed.access_token, parsed.refresh_token))
+    }
+
+    async fn ensure_fresh_token(&self) -> AppResult<()> {
+        let Some(session_lock) = &self.user_session else {
+            return Ok(());
+        };
+
+        let (needs_refresh, refresh_token) = {
+            let session = session_lock
+                .lock()
+                .map_err(|_| AppError::Unexpected("failed to lock user session".to_string()))?;
+            (
+                session.expires_at - Utc::now().timestamp() < REFRESH_SKEW_SECONDS,
+                session.refresh_token.clone(),
+            )
+        };
+
+        let Some(refresh_token) = (if needs_refresh { refresh_token } else { None }) else {
+            return Ok(());
+        };
+
+        #[derive(Deserialize)]
+        struct RefreshResponse {
+            access_token: String,
+            refresh_token: Option<String>,
+        }
+
+        let url = format!("{}/auth/v1/token?grant_type=refresh_token", self.base_url);
+        let resp = self
+            .http
+            .post(url)
+            .header("apikey", &self.api_key)
+            .json(&serde_json::json!({ "refresh_token": refresh_token }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            // Best-effort refresh: let the caller's request proceed and surface the real
+            // auth failure (expired/invalid token) from PostgREST itself.
+            return Ok(());
+        }
+
+        let parsed: RefreshResponse = resp.json().await?;
+        let expires_at = decode_jwt_exp(&parsed.access_token).unwrap_or(0);
+
+        let mut session = session_lock
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock user session".to_string()))?;
+        session.access_token = parsed.access_token;
+        if parsed.refresh_token.is_some() {
+            session.refresh_token = parsed.refresh_token;
+        }
+        session.expires_at = expires_at;
+
+        Ok(())
+    }
+
     fn base_headers(&self, extra: Option<HeaderMap>) -> AppResult<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(
             "apikey",
             HeaderValue::from_str(&self.api_key).map_err(|e| AppError::Unexpected(e.to_string()))?,
         );
+
+        let bearer = match &self.user_session {
+            Some(session_lock) => {
+                let session = session_lock
+                    .lock()
+                    .map_err(|_| AppError::Unexpected("failed to lock user session".to_string()))?;
+                session.access_token.clone()
+            }
+            None => self.api_key.clone(),
+        };
         headers.insert(
             "authorization",
-            HeaderValue::from_str(&format!("Bearer {}", self.api_key))
-                .map_err(|e| AppError::Unexpected(e.to_string()))?,
+            HeaderValue::from_str(&format!("Bearer {}", bearer)).map_err(|e| AppError::Unexpected(e.to_string()))?,
         );
 
         if let Some(extra) = extra {
@@ -64,8 +203,10 @@ impl SupabaseRestClient {
         }
     }
 
-    pub async fn select<T: DeserializeOwned>(&self, table: &str, query: &str) -> AppResult<Vec<T>> {
-        let url = self.rest_url(table, Some(query));
+    pub async fn select<T: DeserializeOwned>(&self, table: &str, query: impl Into<QuerySpec>) -> AppResult<Vec<T>> {
+        self.ensure_fresh_token().await?;
+        let query = query.into().0;
+        let url = self.rest_url(table, Some(&query));
         let resp = self
             .http
             .get(url)
@@ -76,16 +217,48 @@ impl SupabaseRestClient {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            return Err(AppError::Unexpected(format!(
-                "supabase select failed: {} {}",
-                status, body
-            )));
+            return Err(map_postgrest_error(status, &body, "select"));
         }
 
         Ok(resp.json::<Vec<T>>().await?)
     }
 
+    /// Like `select`, but also asks PostgREST for an exact row count via
+    /// `Prefer: count=exact` and parses it out of the `Content-Range` response header
+    /// (e.g. `0-24/573`), so callers can expose pagination totals without downloading
+    /// every row. The count is `None` if the header is missing or unparsable.
+    pub async fn select_with_count<T: DeserializeOwned>(&self, table: &str, query: impl Into<QuerySpec>) -> AppResult<(Vec<T>, Option<u64>)> {
+        self.ensure_fresh_token().await?;
+        let query = query.into().0;
+        let url = self.rest_url(table, Some(&query));
+        let mut extra = HeaderMap::new();
+        extra.insert("prefer", HeaderValue::from_static("count=exact"));
+
+        let resp = self
+            .http
+            .get(url)
+            .headers(self.base_headers(Some(extra))?)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(map_postgrest_error(status, &body, "select"));
+        }
+
+        let total = resp
+            .headers()
+            .get("content-range")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_content_range_total);
+
+        let rows = resp.json::<Vec<T>>().await?;
+        Ok((rows, total))
+    }
+
     pub async fn insert<T: DeserializeOwned, B: Serialize>(&self, table: &str, body: &B) -> AppResult<Vec<T>> {
+        self.ensure_fresh_token().await?;
         let url = self.rest_url(table, Some("select=*"));
         let mut extra = HeaderMap::new();
         extra.insert(
@@ -104,10 +277,7 @@ impl SupabaseRestClient {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            return Err(AppError::Unexpected(format!(
-                "supabase insert failed: {} {}",
-                status, body
-            )));
+            return Err(map_postgrest_error(status, &body, "insert"));
         }
 
         Ok(resp.json::<Vec<T>>().await?)
@@ -116,9 +286,11 @@ impl SupabaseRestClient {
     pub async fn update<T: DeserializeOwned, B: Serialize>(
         &self,
         table: &str,
-        filter_query: &str,
+        filter_query: impl Into<QuerySpec>,
         body: &B,
     ) -> AppResult<Vec<T>> {
+        self.ensure_fresh_token().await?;
+        let filter_query = filter_query.into().0;
         let url = self.rest_url(table, Some(&format!("{}&select=*", filter_query)));
         let mut extra = HeaderMap::new();
         extra.insert(
@@ -137,16 +309,15 @@ impl SupabaseRestClient {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            return Err(AppError::Unexpected(format!(
-                "supabase update failed: {} {}",
-                status, body
-            )));
+            return Err(map_postgrest_error(status, &body, "update"));
         }
 
         Ok(resp.json::<Vec<T>>().await?)
     }
 
-    pub async fn delete<T: DeserializeOwned>(&self, table: &str, filter_query: &str) -> AppResult<Vec<T>> {
+    pub async fn delete<T: DeserializeOwned>(&self, table: &str, filter_query: impl Into<QuerySpec>) -> AppResult<Vec<T>> {
+        self.ensure_fresh_token().await?;
+        let filter_query = filter_query.into().0;
         let url = self.rest_url(table, Some(&format!("{}&select=*", filter_query)));
         let mut extra = HeaderMap::new();
         extra.insert(
@@ -164,12 +335,88 @@ impl SupabaseRestClient {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            return Err(AppError::Unexpected(format!(
-                "supabase delete failed: {} {}",
-                status, body
-            )));
+            return Err(map_postgrest_error(status, &body, "delete"));
         }
 
         Ok(resp.json::<Vec<T>>().await?)
     }
 }
+
+#[derive(Debug, Deserialize)]
+struct PostgrestError {
+    code: Option<String>,
+    message: Option<String>,
+    #[allow(dead_code)]
+    details: Option<String>,
+    hint: Option<String>,
+}
+
+/// Maps a failed PostgREST response into a typed `AppError`, parsing the
+/// `{ code, message, details, hint }` body PostgREST emits when it can. `code` is the
+/// Postgres SQLSTATE, which is specific enough to distinguish e.g. a unique-constraint
+/// violation from an RLS denial.
+fn map_postgrest_error(status: reqwest::StatusCode, body: &str, verb: &str) -> AppError {
+    let parsed: Option<PostgrestError> = serde_json::from_str(body).ok();
+    let reason = |parsed: &Option<PostgrestError>| -> String {
+        match parsed {
+            Some(e) => match (&e.message, &e.hint) {
+                (Some(message), Some(hint)) => format!("{} ({})", message, hint),
+                (Some(message), None) => message.clone(),
+                _ => format!("supabase {} failed: {} {}", verb, status, body),
+            },
+            None => format!("supabase {} failed: {} {}", verb, status, body),
+        }
+    };
+
+    if let Some(code) = parsed.as_ref().and_then(|e| e.code.as_deref()) {
+        match code {
+            "23505" => return AppError::Conflict(reason(&parsed)),
+            "23503" => return AppError::Validation(reason(&parsed)),
+            _ => {}
+        }
+    }
+
+    match status.as_u16() {
+        401 | 403 => AppError::Unauthorized(reason(&parsed)),
+        404 => AppError::NotFound(reason(&parsed)),
+        // Server-side failures (including an empty body, which Supabase/PostgREST commonly
+        // sends for a 5xx) are never "not found" — surfacing them as such would hide a real
+        // outage behind a misleading empty-result error.
+        500..=599 => AppError::Unexpected(format!("supabase {} failed: {} {}", verb, status, body)),
+        _ if body.trim().is_empty() => AppError::NotFound(format!("supabase {} found nothing", verb)),
+        _ => AppError::Unexpected(reason(&parsed)),
+    }
+}
+
+/// Parses the total out of a PostgREST `Content-Range` header, e.g. `"0-24/573"` -> `573`.
+/// An unknown total is sent as `"*"`, which we treat the same as a missing header.
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    let total = value.split('/').nth(1)?;
+    total.parse().ok()
+}
+
+fn decode_jwt_exp(token: &str) -> Option<i64> {
+    let payload_b64 = token.split('.').nth(1)?;
+    let bytes = base64_url_decode(payload_b64)?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    value.get("exp")?.as_i64()
+}
+
+fn base64_url_decode(input: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0u32;
+
+    for c in input.chars() {
+        let value = ALPHABET.iter().position(|&b| b as char == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+
+    Some(out)
+}
@@ -42,6 +42,27 @@ impl UsersRepo {
             .await
     }
 
+    /// Like `list`, but also returns the total row count reported by PostgREST, for UI
+    /// pagination that needs a total page count without downloading every row.
+    pub async fn list_with_count(&self, limit: u32, offset: u32) -> AppResult<(Vec<User>, Option<u64>)> {
+        self.client
+            .select_with_count::<User>(
+                "users",
+                &format!("select=*&order=created_at.desc&limit={}&offset={}", limit, offset),
+            )
+            .await
+    }
+
+    /// Counts users with the given role without downloading any rows.
+    pub async fn count_by_role(&self, role: &str) -> AppResult<u64> {
+        let role = urlencoding::encode(role);
+        let (_, total) = self
+            .client
+            .select_with_count::<User>("users", &format!("role=eq.{}&limit=0", role))
+            .await?;
+        Ok(total.unwrap_or(0))
+    }
+
     pub async fn update(&self, user_id: Uuid, patch: &UpdateUser) -> AppResult<User> {
         let rows = self
             .client
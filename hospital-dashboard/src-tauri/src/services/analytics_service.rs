@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::{
+    entities::{
+        analytics::{AnalyticsFilter, Bucket, BucketedSummary},
+        authz::Action,
+    },
+    error::AppResult,
+    ids::PublicId,
+    repositories::{analytics_repo::AnalyticsRepo, supabase::SupabaseRestClient},
+    services::authz_service::AuthzService,
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct AnalyticsService {
+    client: SupabaseRestClient,
+    repo: AnalyticsRepo,
+    authz: AuthzService,
+}
+
+impl AnalyticsService {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self {
+            repo: AnalyticsRepo::new(client.clone()),
+            authz: AuthzService::new(client.clone()),
+            client,
+        }
+    }
+
+    /// Returns a clone of this service whose repo is scoped to the logged-in user's GoTrue
+    /// JWT, so PostgREST RLS is evaluated as that user rather than under the shared anon key.
+    fn scoped(&self, app: &AppState) -> AppResult<Self> {
+        let client = app.scoped_client(&self.client)?;
+        Ok(Self {
+            repo: AnalyticsRepo::new(client.clone()),
+            authz: self.authz.clone(),
+            client,
+        })
+    }
+
+    pub async fn appointments_by_status(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<BucketedSummary> {
+        self.authz.require_permission(app, "appointments", Action::Read).await?;
+        let appointments = self.scoped(app)?.repo.appointments(&filter).await?;
+        Ok(summarize(
+            appointments.into_iter().map(|a| a.status.unwrap_or_else(|| "unknown".to_string())),
+        ))
+    }
+
+    pub async fn appointments_by_day(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<BucketedSummary> {
+        self.authz.require_permission(app, "appointments", Action::Read).await?;
+        let appointments = self.scoped(app)?.repo.appointments(&filter).await?;
+        Ok(summarize(appointments.into_iter().map(|a| a.appointment_date.to_string())))
+    }
+
+    pub async fn appointments_by_doctor(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<BucketedSummary> {
+        self.authz.require_permission(app, "appointments", Action::Read).await?;
+        let appointments = self.scoped(app)?.repo.appointments(&filter).await?;
+        Ok(summarize(appointments.into_iter().filter_map(|a| a.doctor_id).map(PublicId::encode)))
+    }
+
+    pub async fn most_prescribed_medicines(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<BucketedSummary> {
+        self.authz.require_permission(app, "medications", Action::Read).await?;
+        let medications = self.scoped(app)?.repo.active_medications(&filter).await?;
+        Ok(summarize(medications.into_iter().map(|m| m.medicine_name)))
+    }
+
+    pub async fn report_volume_by_clinic(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<BucketedSummary> {
+        self.authz.require_permission(app, "reports", Action::Read).await?;
+        let reports = self.scoped(app)?.repo.medical_reports(&filter).await?;
+        Ok(summarize(reports.into_iter().filter_map(|r| r.clinic_id).map(|id| id.to_string())))
+    }
+
+    pub async fn active_medication_count(&self, app: &AppState, filter: AnalyticsFilter) -> AppResult<usize> {
+        self.authz.require_permission(app, "medications", Action::Read).await?;
+        Ok(self.scoped(app)?.repo.active_medications(&filter).await?.len())
+    }
+}
+
+fn summarize(values: impl Iterator<Item = String>) -> BucketedSummary {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+
+    let mut buckets: Vec<Bucket> = counts.into_iter().map(|(bucket, count)| Bucket { bucket, count }).collect();
+    buckets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.bucket.cmp(&b.bucket)));
+
+    let total = buckets.iter().map(|b| b.count).sum();
+    BucketedSummary { buckets, total }
+}
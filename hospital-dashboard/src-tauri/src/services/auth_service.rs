@@ -1,34 +1,89 @@
-use chrono::{Duration, Utc};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 use sha2::{Digest, Sha256};
 use crate::{
-    entities::user::{UpdateUser, UserPublic},
+    entities::user::{LoginOutcome, UpdateUser, User, UserPublic},
     error::{AppError, AppResult},
     repositories::{supabase::SupabaseRestClient, users_repo::UsersRepo},
-    services::email_service::EmailService,
-    state::AppState,
+    services::{email_service::EmailService, totp_service::TotpService},
+    state::{AppState, PendingTotpLogin},
 };
 
 #[derive(Clone)]
 pub struct AuthService {
     users: UsersRepo,
+    client: SupabaseRestClient,
 }
 
 impl AuthService {
     pub fn new(client: SupabaseRestClient) -> Self {
         Self {
-            users: UsersRepo::new(client),
+            users: UsersRepo::new(client.clone()),
+            client,
         }
     }
 
-    pub fn sha256_hex(password: &str) -> String {
+    /// Legacy (pre-Argon2) hashing scheme, kept only so `login` can still verify accounts
+    /// that have not logged in since the Argon2id migration shipped.
+    fn sha256_hex(password: &str) -> String {
         let mut h = Sha256::new();
         h.update(password.as_bytes());
         format!("{:x}", h.finalize())
     }
 
-    pub async fn login(&self, app: &AppState, email: &str, password: &str) -> AppResult<UserPublic> {
-        let user = self
+    fn argon2() -> Argon2<'static> {
+        // m=19 MiB, t=2, p=1.
+        let params = Params::new(19 * 1024, 2, 1, None).expect("valid argon2 params");
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    }
+
+    /// Hashes a password into a PHC-format Argon2id string (`$argon2id$v=19$...`).
+    pub fn hash_password(password: &str) -> AppResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        Self::argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::Unexpected(format!("failed to hash password: {e}")))
+    }
+
+    /// Verifies `password` against a stored PHC-format Argon2id hash.
+    fn verify_password(password: &str, phc_hash: &str) -> AppResult<bool> {
+        let parsed = PasswordHash::new(phc_hash)
+            .map_err(|e| AppError::Unexpected(format!("invalid stored password hash: {e}")))?;
+        Ok(Self::argon2().verify_password(password.as_bytes(), &parsed).is_ok())
+    }
+
+    /// A stored SHA-256 hash is always a 64-char lowercase hex digest; Argon2id's PHC strings
+    /// always start with `$argon2`. That's enough to tell the two formats apart unambiguously.
+    fn is_legacy_sha256_hash(stored: &str) -> bool {
+        stored.len() == 64 && stored.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    /// Password reset codes are short-lived, low-entropy digits; storing a hash rather than
+    /// the plaintext means a leaked `users` row can't be replayed directly.
+    fn hash_reset_code(code: &str) -> String {
+        Self::sha256_hex(code)
+    }
+
+    /// Compares two byte strings in time independent of where they first differ, so the
+    /// reset-code check can't be used as a timing oracle.
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+
+    /// Authenticates on email/password alone. If the account has TOTP enabled, the session is
+    /// *not* established yet: the password-verified user and GoTrue tokens are stashed in
+    /// `session.pending_totp` and `LoginOutcome::TotpRequired` is returned, to be completed by
+    /// `login_verify_totp`.
+    pub async fn login(&self, app: &AppState, email: &str, password: &str) -> AppResult<LoginOutcome> {
+        let mut user = self
             .users
             .get_by_email(email)
             .await?
@@ -38,30 +93,211 @@ impl AuthService {
             return Err(AppError::Unauthorized("account disabled".to_string()));
         }
 
-        let expected = user
+        let stored_hash = user
             .password_hash
             .clone()
             .ok_or_else(|| AppError::Unauthorized("invalid email or password".to_string()))?;
-        let provided = Self::sha256_hex(password);
-        if expected != provided {
+
+        if Self::is_legacy_sha256_hash(&stored_hash) {
+            if !Self::constant_time_eq(Self::sha256_hex(password).as_bytes(), stored_hash.as_bytes()) {
+                return Err(AppError::Unauthorized("invalid email or password".to_string()));
+            }
+
+            // Lazy migration: the legacy hash just verified, so re-hash with Argon2id now.
+            let migrated_hash = Self::hash_password(password)?;
+            user = self
+                .users
+                .update(
+                    user.user_id,
+                    &UpdateUser {
+                        password_hash: Some(migrated_hash),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        } else if !Self::verify_password(password, &stored_hash)? {
             return Err(AppError::Unauthorized("invalid email or password".to_string()));
         }
 
+        // Best-effort: obtain a per-user GoTrue JWT so downstream Supabase REST calls can be
+        // scoped to this user under RLS instead of always falling back to the anon key.
+        let user_session = self.client.exchange_password_for_token(email, password).await.ok();
+        let (access_token, refresh_token) = match user_session {
+            Some((access_token, refresh_token)) => (Some(access_token), refresh_token),
+            None => (None, None),
+        };
+
+        let mut session = app
+            .session
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+
+        if user.totp_enabled == Some(true) && user.totp_secret.is_some() {
+            session.pending_totp = Some(PendingTotpLogin {
+                user,
+                access_token,
+                refresh_token,
+            });
+            return Ok(LoginOutcome::TotpRequired);
+        }
+
+        session.current_user = Some(user.clone());
+        session.access_token = access_token;
+        session.refresh_token = refresh_token;
+
+        Ok(LoginOutcome::Authenticated(UserPublic::from(user)))
+    }
+
+    /// Completes a login that returned `LoginOutcome::TotpRequired`, verifying the TOTP code
+    /// against the pending user stashed by `login` and establishing the session on success.
+    pub async fn login_verify_totp(&self, app: &AppState, email: &str, code: &str) -> AppResult<UserPublic> {
+        let pending: PendingTotpLogin = {
+            let mut session = app
+                .session
+                .lock()
+                .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+            match &session.pending_totp {
+                Some(pending) if pending.user.email == email => session.pending_totp.take().unwrap(),
+                _ => return Err(AppError::Unauthorized("no pending totp login for this account".to_string())),
+            }
+        };
+
+        let PendingTotpLogin {
+            user,
+            access_token,
+            refresh_token,
+        } = pending;
+
+        let secret = user
+            .totp_secret
+            .clone()
+            .ok_or_else(|| AppError::Validation("totp is not enrolled for this account".to_string()))?;
+
+        let now = Utc::now().timestamp() as u64;
+        let accepted_counter = TotpService::verify(&secret, code, now, user.totp_last_counter)?
+            .ok_or_else(|| AppError::Unauthorized("invalid totp code".to_string()))?;
+
+        let user: User = self
+            .users
+            .update(
+                user.user_id,
+                &UpdateUser {
+                    totp_last_counter: Some(accepted_counter),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
         let mut session = app
             .session
             .lock()
             .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
         session.current_user = Some(user.clone());
+        session.access_token = access_token;
+        session.refresh_token = refresh_token;
 
         Ok(UserPublic::from(user))
     }
 
+    /// Returns a `SupabaseRestClient` scoped to the currently logged-in user's GoTrue session,
+    /// if one was obtained at login; otherwise falls back to the anon-scoped client.
+    pub fn scoped_client(&self, app: &AppState) -> AppResult<SupabaseRestClient> {
+        app.scoped_client(&self.client)
+    }
+
+    /// Generates a fresh secret for the logged-in user. `totp_enabled` is left/reset to
+    /// `false` until `verify_totp` confirms the user can actually generate codes from it.
+    pub async fn enroll_totp(&self, app: &AppState) -> AppResult<String> {
+        let user_id = {
+            let session = app
+                .session
+                .lock()
+                .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+            let user = session
+                .current_user
+                .as_ref()
+                .ok_or_else(|| AppError::Unauthorized("not logged in".to_string()))?;
+            user.user_id
+        };
+
+        let secret = TotpService::generate_secret();
+        let updated = self
+            .users
+            .update(
+                user_id,
+                &UpdateUser {
+                    totp_secret: Some(secret.clone()),
+                    totp_last_counter: None,
+                    totp_enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let mut session = app
+            .session
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+        session.current_user = Some(updated.clone());
+
+        Ok(TotpService::provisioning_uri(&updated.email, &secret))
+    }
+
+    /// Confirms a code against the secret `enroll_totp` generated. The first successful call
+    /// also flips `totp_enabled`, so TOTP only becomes mandatory at login once the user has
+    /// proven they can actually generate codes from it.
+    pub async fn verify_totp(&self, app: &AppState, code: &str) -> AppResult<bool> {
+        let (user_id, secret, last_counter, totp_enabled) = {
+            let session = app
+                .session
+                .lock()
+                .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+            let user = session
+                .current_user
+                .as_ref()
+                .ok_or_else(|| AppError::Unauthorized("not logged in".to_string()))?;
+            let secret = user
+                .totp_secret
+                .clone()
+                .ok_or_else(|| AppError::Validation("totp is not enrolled for this account".to_string()))?;
+            (user.user_id, secret, user.totp_last_counter, user.totp_enabled)
+        };
+
+        let now = Utc::now().timestamp() as u64;
+        let accepted_counter = match TotpService::verify(&secret, code, now, last_counter)? {
+            Some(counter) => counter,
+            None => return Ok(false),
+        };
+
+        let updated = self
+            .users
+            .update(
+                user_id,
+                &UpdateUser {
+                    totp_last_counter: Some(accepted_counter),
+                    totp_enabled: if totp_enabled == Some(true) { None } else { Some(true) },
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let mut session = app
+            .session
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+        session.current_user = Some(updated);
+
+        Ok(true)
+    }
+
     pub fn logout(&self, app: &AppState) -> AppResult<()> {
         let mut session = app
             .session
             .lock()
             .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
         session.current_user = None;
+        session.access_token = None;
+        session.refresh_token = None;
         Ok(())
     }
 
@@ -87,7 +323,7 @@ impl AuthService {
         let expires = (Utc::now() + Duration::minutes(15)).to_rfc3339();
 
         let patch = UpdateUser {
-            password_reset_token: Some(code.clone()),
+            password_reset_token: Some(Self::hash_reset_code(&code)),
             password_reset_expires: Some(expires.clone()),
             ..Default::default()
         };
@@ -110,22 +346,26 @@ impl AuthService {
             .await?
             .ok_or_else(|| AppError::Unauthorized("invalid reset code".to_string()))?;
 
-        let token = user
+        let token_hash = user
             .password_reset_token
             .clone()
             .ok_or_else(|| AppError::Unauthorized("invalid reset code".to_string()))?;
 
-        if token != code {
+        if !Self::constant_time_eq(token_hash.as_bytes(), Self::hash_reset_code(code).as_bytes()) {
             return Err(AppError::Unauthorized("invalid reset code".to_string()));
         }
 
-        // We stored ISO string; simplest is to trust DB-side checks later.
-        // For now, just require it exists.
-        if user.password_reset_expires.is_none() {
+        let expires_at = user
+            .password_reset_expires
+            .as_deref()
+            .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+            .ok_or_else(|| AppError::Unauthorized("reset code expired".to_string()))?;
+
+        if expires_at < Utc::now() {
             return Err(AppError::Unauthorized("reset code expired".to_string()));
         }
 
-        let new_hash = Self::sha256_hex(new_password);
+        let new_hash = Self::hash_password(new_password)?;
 
         let patch = UpdateUser {
             password_hash: Some(new_hash),
@@ -139,3 +379,37 @@ impl AuthService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_password_roundtrips_through_verify() {
+        let hash = AuthService::hash_password("correct horse battery staple").unwrap();
+        assert!(AuthService::verify_password("correct horse battery staple", &hash).unwrap());
+        assert!(!AuthService::verify_password("wrong password", &hash).unwrap());
+    }
+
+    #[test]
+    fn hash_password_produces_an_argon2id_phc_string() {
+        let hash = AuthService::hash_password("hunter2").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+    }
+
+    #[test]
+    fn legacy_sha256_hashes_are_recognized_by_shape_not_content() {
+        let legacy = AuthService::sha256_hex("hunter2");
+        assert!(AuthService::is_legacy_sha256_hash(&legacy));
+
+        let migrated = AuthService::hash_password("hunter2").unwrap();
+        assert!(!AuthService::is_legacy_sha256_hash(&migrated));
+    }
+
+    #[test]
+    fn constant_time_eq_only_matches_identical_bytes() {
+        assert!(AuthService::constant_time_eq(b"abc123", b"abc123"));
+        assert!(!AuthService::constant_time_eq(b"abc123", b"abc124"));
+        assert!(!AuthService::constant_time_eq(b"short", b"much longer"));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    entities::authz::Action,
+    error::{AppError, AppResult},
+    repositories::{authz_repo::AuthzRepo, supabase::SupabaseRestClient},
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct AuthzService {
+    roles: AuthzRepo,
+}
+
+impl AuthzService {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self {
+            roles: AuthzRepo::new(client),
+        }
+    }
+
+    pub async fn require_permission(&self, app: &AppState, resource: &str, action: Action) -> AppResult<()> {
+        let role_name = {
+            let session = app
+                .session
+                .lock()
+                .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+            let user = session
+                .current_user
+                .as_ref()
+                .ok_or_else(|| AppError::Unauthorized("not logged in".to_string()))?;
+            user.role
+                .clone()
+                .ok_or_else(|| AppError::Unauthorized("account has no role assigned".to_string()))?
+        };
+
+        let role = self
+            .roles
+            .get_role_by_name(&role_name)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized(format!("unknown role: {}", role_name)))?;
+
+        let granted = role
+            .permissions
+            .iter()
+            .any(|p| (p.resource == "*" || p.resource == resource) && p.action == action);
+
+        if !granted {
+            return Err(AppError::Unauthorized(format!(
+                "missing permission: {:?} on {}",
+                action, resource
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_role_names(&self) -> AppResult<Vec<String>> {
+        self.roles.list_role_names().await
+    }
+}
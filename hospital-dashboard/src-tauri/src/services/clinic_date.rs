@@ -1,28 +1,72 @@
 use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
 
 use crate::error::{AppError, AppResult};
 
-pub fn fourth_tuesday(year: i32, month: u32) -> AppResult<NaiveDate> {
+/// A recurring clinic date rule: the `ordinal`-th occurrence of `weekday` in a month
+/// (`ordinal == 5` means "the last occurrence, whatever the month length"), repeating every
+/// `interval_months` months. Lets individual clinics declare their own recurring schedule
+/// instead of being locked to a single hard-coded rule.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ClinicSchedule {
+    pub weekday: Weekday,
+    pub ordinal: u8,
+    pub interval_months: u32,
+}
+
+impl ClinicSchedule {
+    /// The schedule HealHub used before clinics could configure their own: 4th Tuesday, monthly.
+    pub const DEFAULT: ClinicSchedule = ClinicSchedule {
+        weekday: Weekday::Tue,
+        ordinal: 4,
+        interval_months: 1,
+    };
+}
+
+/// Finds the `ordinal`-th `weekday` of `year`/`month`. For `ordinal` 1..=4 this walks forward
+/// from the 1st; for `ordinal == 5` ("last occurrence") it walks backward from the 1st of the
+/// following month, so "last Tuesday" is always valid regardless of how many Tuesdays the
+/// month has.
+pub fn nth_weekday_of_month(year: i32, month: u32, weekday: Weekday, ordinal: u8) -> AppResult<NaiveDate> {
+    if !(1..=5).contains(&ordinal) {
+        return Err(AppError::Validation("ordinal must be in 1..=5".to_string()));
+    }
+
+    if ordinal == 5 {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let next_first = NaiveDate::from_ymd_opt(next_year, next_month, 1)
+            .ok_or_else(|| AppError::Validation("invalid year/month".to_string()))?;
+
+        let mut date = next_first - Duration::days(1);
+        while date.weekday() != weekday {
+            date -= Duration::days(1);
+        }
+        return Ok(date);
+    }
+
     let first_day = NaiveDate::from_ymd_opt(year, month, 1)
         .ok_or_else(|| AppError::Validation("invalid year/month".to_string()))?;
 
     let mut date = first_day;
-    while date.weekday() != Weekday::Tue {
-        date = date + Duration::days(1);
+    while date.weekday() != weekday {
+        date += Duration::days(1);
     }
-
-    // First Tuesday found; add 3 weeks to reach 4th Tuesday.
-    Ok(date + Duration::days(21))
+    Ok(date + Duration::days((ordinal as i64 - 1) * 7))
 }
 
-pub fn next_default_clinic_date(from_date: NaiveDate) -> AppResult<NaiveDate> {
+pub fn next_default_clinic_date(from_date: NaiveDate, schedule: ClinicSchedule) -> AppResult<NaiveDate> {
     let year = from_date.year();
     let month = from_date.month();
-    let this_month = fourth_tuesday(year, month)?;
+    let this_month = nth_weekday_of_month(year, month, schedule.weekday, schedule.ordinal)?;
     if this_month > from_date {
         return Ok(this_month);
     }
 
-    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
-    fourth_tuesday(next_year, next_month)
+    let mut next_year = year;
+    let mut next_month = month + schedule.interval_months;
+    while next_month > 12 {
+        next_month -= 12;
+        next_year += 1;
+    }
+    nth_weekday_of_month(next_year, next_month, schedule.weekday, schedule.ordinal)
 }
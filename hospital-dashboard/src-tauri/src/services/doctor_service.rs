@@ -1,81 +1,108 @@
 use chrono::{NaiveTime, Timelike, Utc};
 
 use crate::{
+    crypto::Encrypted,
     entities::{
         appointment::UpdateAppointment,
+        authz::Action,
         history::NewPatientDoctorHistory,
         medication::NewPatientMedication,
-        patient::Patient,
+        pagination::Page,
+        patient::{Patient, PatientPublic},
     },
     error::{AppError, AppResult},
+    export, fhir,
     repositories::{
         appointments_repo::AppointmentsRepo,
         clinic_repo::ClinicRepo,
+        doctors_repo::DoctorsRepo,
         history_repo::HistoryRepo,
         medications_repo::MedicationsRepo,
         patients_repo::PatientsRepo,
         reports_repo::ReportsRepo,
         supabase::SupabaseRestClient,
     },
+    services::authz_service::AuthzService,
     state::AppState,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct PatientOverview {
-    pub patient: Patient,
-    pub appointments: Vec<crate::entities::appointment::Appointment>,
-    pub medications: Vec<crate::entities::medication::PatientMedication>,
+    pub patient: PatientPublic,
+    pub appointments: Vec<crate::entities::appointment::AppointmentPublic>,
+    pub medications: Vec<crate::entities::medication::PatientMedicationPublic>,
     pub clinics: Vec<crate::entities::clinic::ClinicParticipation>,
-    pub history: Vec<crate::entities::history::PatientDoctorHistory>,
-    pub medical_reports: Vec<crate::entities::report::MedicalReport>,
-    pub prescriptions: Vec<crate::entities::report::PrescriptionRecord>,
+    pub history: Vec<crate::entities::history::PatientDoctorHistoryPublic>,
+    pub medical_reports: Vec<crate::entities::report::MedicalReportPublic>,
+    pub prescriptions: Vec<crate::entities::report::PrescriptionRecordPublic>,
 }
 
 #[derive(Clone)]
 pub struct DoctorService {
+    client: SupabaseRestClient,
     patients: PatientsRepo,
+    doctors: DoctorsRepo,
     appointments: AppointmentsRepo,
     meds: MedicationsRepo,
     clinics: ClinicRepo,
     history: HistoryRepo,
     reports: ReportsRepo,
+    authz: AuthzService,
 }
 
 impl DoctorService {
     pub fn new(client: SupabaseRestClient) -> Self {
         Self {
             patients: PatientsRepo::new(client.clone()),
+            doctors: DoctorsRepo::new(client.clone()),
             appointments: AppointmentsRepo::new(client.clone()),
             meds: MedicationsRepo::new(client.clone()),
             clinics: ClinicRepo::new(client.clone()),
             history: HistoryRepo::new(client.clone()),
-            reports: ReportsRepo::new(client),
+            reports: ReportsRepo::new(client.clone()),
+            authz: AuthzService::new(client.clone()),
+            client,
         }
     }
 
-    fn require_doctor(app: &AppState) -> AppResult<()> {
-        let session = app
-            .session
-            .lock()
-            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
-        let user = session
-            .current_user
-            .as_ref()
-            .ok_or_else(|| AppError::Unauthorized("not logged in".to_string()))?;
-        if user.role.as_deref() != Some("doctor") {
-            return Err(AppError::Unauthorized("doctor only".to_string()));
-        }
-        Ok(())
+    /// Returns a clone of this service whose repos are scoped to the logged-in user's GoTrue
+    /// JWT, so PostgREST RLS is evaluated as that user rather than under the shared anon key.
+    /// `authz` is left untouched, since role/permission lookups aren't per-user PHI.
+    pub(crate) fn scoped(&self, app: &AppState) -> AppResult<Self> {
+        let client = app.scoped_client(&self.client)?;
+        Ok(Self {
+            patients: PatientsRepo::new(client.clone()),
+            doctors: DoctorsRepo::new(client.clone()),
+            appointments: AppointmentsRepo::new(client.clone()),
+            meds: MedicationsRepo::new(client.clone()),
+            clinics: ClinicRepo::new(client.clone()),
+            history: HistoryRepo::new(client.clone()),
+            reports: ReportsRepo::new(client.clone()),
+            authz: self.authz.clone(),
+            client,
+        })
     }
 
-    pub async fn list_patients(&self, app: &AppState, limit: u32, offset: u32) -> AppResult<Vec<Patient>> {
-        Self::require_doctor(app)?;
-        self.patients.list(limit, offset).await
+    pub async fn list_patients(&self, app: &AppState, limit: u32, offset: u32) -> AppResult<Page<PatientPublic>> {
+        self.authz.require_permission(app, "patients", Action::Read).await?;
+        let repos = self.scoped(app)?;
+        let (patients, total) = repos.patients.list_with_count(limit, offset).await?;
+        Ok(Page {
+            items: patients.into_iter().map(PatientPublic::from).collect(),
+            total,
+        })
     }
 
     pub async fn get_patient_overview(&self, app: &AppState, patient_id: i32) -> AppResult<PatientOverview> {
-        Self::require_doctor(app)?;
+        self.authz.require_permission(app, "patients", Action::Read).await?;
+        self.scoped(app)?.build_patient_overview(patient_id).await
+    }
 
+    /// Assembles the `PatientOverview` with no permission check of its own. Used by
+    /// `get_patient_overview` (which checks the normal "patients" RBAC permission) and by
+    /// `EmergencyAccessService` (which gates access through an approved break-glass grant
+    /// instead).
+    pub(crate) async fn build_patient_overview(&self, patient_id: i32) -> AppResult<PatientOverview> {
         let patient = self
             .patients
             .get_by_id(patient_id)
@@ -101,39 +128,147 @@ impl DoctorService {
         prescriptions.dedup_by_key(|r| r.prescription_id);
 
         Ok(PatientOverview {
-            patient,
-            appointments,
-            medications,
+            patient: PatientPublic::from(patient),
+            appointments: appointments.into_iter().map(Into::into).collect(),
+            medications: medications.into_iter().map(Into::into).collect(),
             clinics,
-            history,
-            medical_reports,
-            prescriptions,
+            history: history.into_iter().map(Into::into).collect(),
+            medical_reports: medical_reports.into_iter().map(Into::into).collect(),
+            prescriptions: prescriptions.into_iter().map(Into::into).collect(),
         })
     }
 
+    /// Exports a patient's reports, prescriptions, medications, and encounters as a FHIR R4B
+    /// `Bundle` of type `collection`, built directly off the same `PatientOverview` aggregate
+    /// the dashboard uses, so HealHub records can be shared with external EHR/FHIR systems.
+    pub async fn export_patient_fhir(&self, app: &AppState, patient_id: i32) -> AppResult<serde_json::Value> {
+        let overview = self.get_patient_overview(app, patient_id).await?;
+        let repos = self.scoped(app)?;
+
+        let mut doctor_ids: Vec<i32> = overview.appointments.iter().filter_map(|a| a.doctor_id).map(|id| id.0).collect();
+        doctor_ids.extend(overview.history.iter().filter_map(|h| h.doctor_id).map(|id| id.0));
+        doctor_ids.extend(overview.medications.iter().filter_map(|m| m.doctor_id).map(|id| id.0));
+        doctor_ids.extend(overview.medical_reports.iter().filter_map(|r| r.created_by_doctor_id).map(|id| id.0));
+        doctor_ids.extend(overview.prescriptions.iter().filter_map(|r| r.prescribed_by_doctor_id).map(|id| id.0));
+        doctor_ids.sort_unstable();
+        doctor_ids.dedup();
+
+        let patient = repos
+            .patients
+            .get_by_id(patient_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("patient not found".to_string()))?;
+
+        let mut resources = vec![fhir::patient_resource(&patient)];
+
+        for doctor_id in doctor_ids {
+            if let Some(doctor) = repos.doctors.get_by_id(doctor_id).await? {
+                resources.push(fhir::practitioner_resource(&doctor));
+            }
+        }
+
+        for appointment in &overview.appointments {
+            resources.push(fhir::encounter_resource(appointment));
+        }
+
+        for history in &overview.history {
+            resources.push(fhir::encounter_from_history(history));
+        }
+
+        for report in &overview.medical_reports {
+            let encounter_id = report.appointment_id.map(|id| id.to_string());
+            resources.push(fhir::diagnostic_report_resource(report, encounter_id.as_deref()));
+            resources.push(fhir::condition_resource(report, Some(patient_id)));
+        }
+
+        for medication in &overview.medications {
+            resources.push(fhir::medication_statement_resource(medication));
+        }
+
+        for prescription in &overview.prescriptions {
+            resources.push(fhir::medication_request_from_prescription(prescription));
+        }
+
+        Ok(fhir::bundle(resources))
+    }
+
+    /// Renders the `MedicalReport` for an appointment (plus its prescriptions and the
+    /// patient's active medications) as printable HTML, for the Tauri print-to-PDF path.
+    pub async fn export_report_printable(&self, app: &AppState, appointment_id: i32) -> AppResult<String> {
+        self.authz.require_permission(app, "reports", Action::Read).await?;
+        let repos = self.scoped(app)?;
+
+        let appointment = repos
+            .appointments
+            .get_by_id(appointment_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("appointment not found".to_string()))?;
+
+        let patient_id = appointment
+            .patient_id
+            .ok_or_else(|| AppError::Validation("appointment has no patient".to_string()))?;
+
+        let patient = repos
+            .patients
+            .get_by_id(patient_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("patient not found".to_string()))?;
+
+        let doctor = match appointment.doctor_id {
+            Some(doctor_id) => repos.doctors.get_by_id(doctor_id).await?,
+            None => None,
+        };
+
+        let report = repos
+            .reports
+            .medical_reports_for_appointment_ids(&[appointment_id])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Validation("no medical report for this appointment".to_string()))?;
+
+        let prescriptions = repos.reports.prescriptions_for_appointment_ids(&[appointment_id]).await?;
+
+        let medications: Vec<_> = repos
+            .meds
+            .list_for_patient(patient_id, 200)
+            .await?
+            .into_iter()
+            .filter(|m| m.is_active.unwrap_or(false))
+            .collect();
+
+        Ok(export::report_printable_html(
+            &report,
+            &prescriptions,
+            &medications,
+            &patient,
+            doctor.as_ref(),
+        ))
+    }
+
     pub async fn list_appointments(&self, app: &AppState, doctor_id: i32, limit: u32, offset: u32) -> AppResult<Vec<crate::entities::appointment::Appointment>> {
-        Self::require_doctor(app)?;
-        self.appointments.list_for_doctor(doctor_id, limit, offset).await
+        self.authz.require_permission(app, "appointments", Action::Read).await?;
+        self.scoped(app)?.appointments.list_for_doctor(doctor_id, limit, offset).await
     }
 
     pub async fn accept_appointment(&self, app: &AppState, appointment_id: i32) -> AppResult<crate::entities::appointment::Appointment> {
-        Self::require_doctor(app)?;
+        self.authz.require_permission(app, "appointments", Action::Write).await?;
         let patch = UpdateAppointment {
             status: Some("Confirmed".to_string()),
             checked_by_doctor_at: Some(Utc::now()),
             notes: None,
         };
-        self.appointments.update(appointment_id, &patch).await
+        self.scoped(app)?.appointments.update(appointment_id, &patch).await
     }
 
     pub async fn reject_appointment(&self, app: &AppState, appointment_id: i32, reason: Option<String>) -> AppResult<crate::entities::appointment::Appointment> {
-        Self::require_doctor(app)?;
+        self.authz.require_permission(app, "appointments", Action::Write).await?;
         let patch = UpdateAppointment {
             status: Some("Cancelled".to_string()),
             checked_by_doctor_at: Some(Utc::now()),
             notes: reason,
         };
-        self.appointments.update(appointment_id, &patch).await
+        self.scoped(app)?.appointments.update(appointment_id, &patch).await
     }
 
     pub async fn add_medication(
@@ -141,8 +276,8 @@ impl DoctorService {
         app: &AppState,
         new_med: NewPatientMedication,
     ) -> AppResult<crate::entities::medication::PatientMedication> {
-        Self::require_doctor(app)?;
-        self.meds.insert(&new_med).await
+        self.authz.require_permission(app, "medications", Action::Write).await?;
+        self.scoped(app)?.meds.insert(&new_med).await
     }
 
     pub async fn record_patient_visit(
@@ -152,7 +287,7 @@ impl DoctorService {
         doctor_id: i32,
         notes: Option<String>,
     ) -> AppResult<crate::entities::history::PatientDoctorHistory> {
-        Self::require_doctor(app)?;
+        self.authz.require_permission(app, "history", Action::Write).await?;
 
         let now = Utc::now();
         let new_row = NewPatientDoctorHistory {
@@ -161,9 +296,9 @@ impl DoctorService {
             encounter_type: "Consultation".to_string(),
             encounter_date: now.date_naive(),
             encounter_time: NaiveTime::from_hms_opt(now.hour(), now.minute(), now.second()),
-            notes,
+            notes: notes.map(Encrypted::from),
         };
 
-        self.history.insert(&new_row).await
+        self.scoped(app)?.history.insert(&new_row).await
     }
 }
@@ -0,0 +1,317 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        authz::Action,
+        emergency_access::{EmergencyAccess, EmergencyAccessStatus, NewEmergencyAccess, UpdateEmergencyAccess},
+    },
+    error::{AppError, AppResult},
+    repositories::{emergency_access_repo::EmergencyAccessRepo, supabase::SupabaseRestClient, users_repo::UsersRepo},
+    services::{authz_service::AuthzService, doctor_service::DoctorService, email_service::EmailService},
+    state::AppState,
+};
+
+/// Break-glass access to a patient overview for a non-treating clinician, modeled as a
+/// grantor/grantee escrow with a time-delayed recovery path: the grantee requests access, the
+/// grantor can approve immediately, and otherwise the grant auto-activates once
+/// `wait_time_days` has elapsed since recovery was initiated.
+#[derive(Clone)]
+pub struct EmergencyAccessService {
+    client: SupabaseRestClient,
+    access: EmergencyAccessRepo,
+    users: UsersRepo,
+    doctor: DoctorService,
+    authz: AuthzService,
+}
+
+impl EmergencyAccessService {
+    pub fn new(client: SupabaseRestClient, doctor: DoctorService) -> Self {
+        Self {
+            access: EmergencyAccessRepo::new(client.clone()),
+            users: UsersRepo::new(client.clone()),
+            doctor,
+            authz: AuthzService::new(client.clone()),
+            client,
+        }
+    }
+
+    /// Returns a clone of this service whose repos (and its `DoctorService`) are scoped to the
+    /// logged-in user's GoTrue JWT, so PostgREST RLS is evaluated as that user rather than
+    /// under the shared anon key.
+    fn scoped(&self, app: &AppState) -> AppResult<Self> {
+        let client = app.scoped_client(&self.client)?;
+        Ok(Self {
+            access: EmergencyAccessRepo::new(client.clone()),
+            users: UsersRepo::new(client.clone()),
+            doctor: self.doctor.clone(),
+            authz: self.authz.clone(),
+            client,
+        })
+    }
+
+    /// Whether a break-glass grant currently authorizes access: either the grantor approved
+    /// the recovery outright, or `wait_time_days` has elapsed since the grantee initiated it.
+    fn is_access_granted(
+        status: EmergencyAccessStatus,
+        recovery_initiated_at: Option<DateTime<Utc>>,
+        wait_time_days: i32,
+        now: DateTime<Utc>,
+    ) -> bool {
+        match status {
+            EmergencyAccessStatus::RecoveryApproved => true,
+            EmergencyAccessStatus::RecoveryInitiated => recovery_initiated_at
+                .map(|initiated_at| initiated_at + Duration::days(wait_time_days as i64) <= now)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    fn current_user_id(app: &AppState) -> AppResult<Uuid> {
+        let session = app
+            .session
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+        let user = session
+            .current_user
+            .as_ref()
+            .ok_or_else(|| AppError::Unauthorized("not logged in".to_string()))?;
+        Ok(user.user_id)
+    }
+
+    pub async fn invite(
+        &self,
+        app: &AppState,
+        grantee_user_id: Uuid,
+        patient_id: i32,
+        wait_time_days: i32,
+    ) -> AppResult<EmergencyAccess> {
+        self.authz.require_permission(app, "emergency_access", Action::Write).await?;
+        let grantor_user_id = Self::current_user_id(app)?;
+
+        if wait_time_days < 1 {
+            return Err(AppError::Validation("wait_time_days must be at least 1".to_string()));
+        }
+        if grantee_user_id == grantor_user_id {
+            return Err(AppError::Validation("cannot grant emergency access to yourself".to_string()));
+        }
+
+        let new_access = NewEmergencyAccess {
+            grantor_user_id,
+            grantee_user_id,
+            patient_id,
+            status: EmergencyAccessStatus::Invited,
+            wait_time_days,
+        };
+
+        self.scoped(app)?.access.insert(&new_access).await
+    }
+
+    pub async fn accept(&self, app: &AppState, access_id: i32) -> AppResult<EmergencyAccess> {
+        let access = self.get_as_grantee(app, access_id).await?;
+
+        if access.status != EmergencyAccessStatus::Invited {
+            return Err(AppError::Validation("emergency access grant is not invited".to_string()));
+        }
+
+        self.scoped(app)?
+            .access
+            .update(
+                access_id,
+                &UpdateEmergencyAccess {
+                    status: Some(EmergencyAccessStatus::Accepted),
+                    ..Default::default()
+                },
+            )
+            .await
+    }
+
+    /// The grantee starts the clock on a time-delayed override, notifying the grantor by
+    /// email so they have a chance to approve (or otherwise intervene) before it auto-grants.
+    pub async fn initiate_recovery(&self, app: &AppState, access_id: i32) -> AppResult<EmergencyAccess> {
+        let access = self.get_as_grantee(app, access_id).await?;
+
+        if access.status != EmergencyAccessStatus::Accepted {
+            return Err(AppError::Validation("emergency access grant has not been accepted".to_string()));
+        }
+
+        let repos = self.scoped(app)?;
+        let now = Utc::now();
+        let updated = repos
+            .access
+            .update(
+                access_id,
+                &UpdateEmergencyAccess {
+                    status: Some(EmergencyAccessStatus::RecoveryInitiated),
+                    recovery_initiated_at: Some(now),
+                    last_notification_at: Some(now),
+                },
+            )
+            .await?;
+
+        if let Some(grantor) = repos.users.get_by_id(access.grantor_user_id).await? {
+            let html = format!(
+                r#"<h2>Emergency Access Requested</h2><p>A clinician has requested emergency access to a patient record. If you don't approve it, access will be granted automatically in {} day(s).</p>"#,
+                access.wait_time_days
+            );
+            EmailService::send_html(&grantor.email, "Emergency Access Requested - HealHub", &html).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// The grantor can approve the recovery immediately instead of waiting out the delay.
+    pub async fn approve_recovery(&self, app: &AppState, access_id: i32) -> AppResult<EmergencyAccess> {
+        let repos = self.scoped(app)?;
+        let access = repos
+            .access
+            .get_by_id(access_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("emergency access grant not found".to_string()))?;
+
+        if Self::current_user_id(app)? != access.grantor_user_id {
+            return Err(AppError::Unauthorized("only the grantor can approve this grant".to_string()));
+        }
+
+        if access.status != EmergencyAccessStatus::RecoveryInitiated {
+            return Err(AppError::Validation("no recovery is in progress for this grant".to_string()));
+        }
+
+        repos
+            .access
+            .update(
+                access_id,
+                &UpdateEmergencyAccess {
+                    status: Some(EmergencyAccessStatus::RecoveryApproved),
+                    ..Default::default()
+                },
+            )
+            .await
+    }
+
+    /// The grantor can reject an in-progress recovery outright, permanently blocking the
+    /// time-delayed auto-grant instead of merely declining to act before it elapses.
+    pub async fn reject_recovery(&self, app: &AppState, access_id: i32) -> AppResult<EmergencyAccess> {
+        let repos = self.scoped(app)?;
+        let access = repos
+            .access
+            .get_by_id(access_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("emergency access grant not found".to_string()))?;
+
+        if Self::current_user_id(app)? != access.grantor_user_id {
+            return Err(AppError::Unauthorized("only the grantor can reject this grant".to_string()));
+        }
+
+        if access.status != EmergencyAccessStatus::RecoveryInitiated {
+            return Err(AppError::Validation("no recovery is in progress for this grant".to_string()));
+        }
+
+        repos
+            .access
+            .update(
+                access_id,
+                &UpdateEmergencyAccess {
+                    status: Some(EmergencyAccessStatus::Rejected),
+                    ..Default::default()
+                },
+            )
+            .await
+    }
+
+    /// Returns the patient overview once the break-glass grant is live: either the grantor
+    /// approved it outright, or the configured wait time has elapsed since recovery began.
+    pub async fn get_patient_overview_via_emergency(
+        &self,
+        app: &AppState,
+        access_id: i32,
+    ) -> AppResult<crate::services::doctor_service::PatientOverview> {
+        let access = self.get_as_grantee(app, access_id).await?;
+
+        let is_granted = Self::is_access_granted(access.status, access.recovery_initiated_at, access.wait_time_days, Utc::now());
+
+        if !is_granted {
+            return Err(AppError::Unauthorized("emergency access has not been granted yet".to_string()));
+        }
+
+        self.doctor.scoped(app)?.build_patient_overview(access.patient_id).await
+    }
+
+    async fn get_as_grantee(&self, app: &AppState, access_id: i32) -> AppResult<EmergencyAccess> {
+        let access = self
+            .scoped(app)?
+            .access
+            .get_by_id(access_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("emergency access grant not found".to_string()))?;
+
+        if Self::current_user_id(app)? != access.grantee_user_id {
+            return Err(AppError::Unauthorized("only the grantee can act on this grant".to_string()));
+        }
+
+        Ok(access)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovery_approved_is_always_granted() {
+        let now = Utc::now();
+        assert!(EmergencyAccessService::is_access_granted(
+            EmergencyAccessStatus::RecoveryApproved,
+            None,
+            7,
+            now
+        ));
+    }
+
+    #[test]
+    fn recovery_initiated_is_not_granted_before_the_wait_time_elapses() {
+        let now = Utc::now();
+        let initiated_at = now - Duration::days(3);
+        assert!(!EmergencyAccessService::is_access_granted(
+            EmergencyAccessStatus::RecoveryInitiated,
+            Some(initiated_at),
+            7,
+            now
+        ));
+    }
+
+    #[test]
+    fn recovery_initiated_is_granted_once_the_wait_time_elapses() {
+        let now = Utc::now();
+        let initiated_at = now - Duration::days(7);
+        assert!(EmergencyAccessService::is_access_granted(
+            EmergencyAccessStatus::RecoveryInitiated,
+            Some(initiated_at),
+            7,
+            now
+        ));
+    }
+
+    #[test]
+    fn recovery_initiated_without_a_timestamp_is_never_granted() {
+        let now = Utc::now();
+        assert!(!EmergencyAccessService::is_access_granted(
+            EmergencyAccessStatus::RecoveryInitiated,
+            None,
+            7,
+            now
+        ));
+    }
+
+    #[test]
+    fn other_statuses_are_never_granted() {
+        let now = Utc::now();
+        for status in [
+            EmergencyAccessStatus::Invited,
+            EmergencyAccessStatus::Accepted,
+            EmergencyAccessStatus::Rejected,
+        ] {
+            assert!(!EmergencyAccessService::is_access_granted(status, None, 7, now));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use serde_json::Value;
+
+use crate::{
+    crypto,
+    entities::authz::Action,
+    error::AppResult,
+    repositories::supabase::SupabaseRestClient,
+    services::authz_service::AuthzService,
+    state::AppState,
+};
+
+struct PhiTable {
+    table: &'static str,
+    id_column: &'static str,
+    fields: &'static [&'static str],
+}
+
+const PHI_TABLES: &[PhiTable] = &[
+    PhiTable {
+        table: "patients",
+        id_column: "patient_id",
+        fields: &["blood_group", "emergency_contact", "condition_notes"],
+    },
+    PhiTable {
+        table: "patient_doctor_history",
+        id_column: "history_id",
+        fields: &["notes"],
+    },
+    PhiTable {
+        table: "patient_medications",
+        id_column: "medication_id",
+        fields: &["notes"],
+    },
+    PhiTable {
+        table: "medical_reports",
+        id_column: "report_id",
+        fields: &["diagnosis", "prescription", "notes"],
+    },
+    PhiTable {
+        table: "prescription_records",
+        id_column: "prescription_id",
+        fields: &["prescription_text"],
+    },
+];
+
+/// Backfills/re-encrypts the `Encrypted<T>` PHI columns across `patients`,
+/// `patient_doctor_history`, `patient_medications`, `medical_reports`, and
+/// `prescription_records`.
+#[derive(Clone)]
+pub struct KeyRotationService {
+    client: SupabaseRestClient,
+    authz: AuthzService,
+}
+
+impl KeyRotationService {
+    pub fn new(client: SupabaseRestClient) -> Self {
+        Self {
+            authz: AuthzService::new(client.clone()),
+            client,
+        }
+    }
+
+    /// Re-encrypts every PHI field under `new_secret`. Pass `old_secret = None` to treat the
+    /// current column value as legacy plaintext rather than ciphertext (first-time backfill).
+    pub async fn migrate(&self, app: &AppState, old_secret: Option<&str>, new_secret: &str) -> AppResult<usize> {
+        self.authz.require_permission(app, "crypto", Action::Write).await?;
+
+        let mut migrated = 0usize;
+
+        for table in PHI_TABLES {
+            let rows: Vec<Value> = self.client.select(table.table, "select=*&limit=10000").await?;
+
+            for row in rows {
+                let Some(id) = row.get(table.id_column).and_then(Value::as_i64) else {
+                    continue;
+                };
+
+                let mut patch = serde_json::Map::new();
+                for &field in table.fields {
+                    let Some(raw) = row.get(field).and_then(Value::as_str) else {
+                        continue;
+                    };
+                    let sealed = crypto::migrate_field(raw, old_secret, new_secret)?;
+                    patch.insert(field.to_string(), Value::String(sealed));
+                }
+
+                if patch.is_empty() {
+                    continue;
+                }
+
+                let _: Vec<Value> = self
+                    .client
+                    .update(table.table, &format!("{}=eq.{}", table.id_column, id), &Value::Object(patch))
+                    .await?;
+                migrated += 1;
+            }
+        }
+
+        Ok(migrated)
+    }
+}
@@ -1,20 +1,89 @@
 use std::sync::Mutex;
 
-use crate::entities::user::User;
+use crate::{
+    entities::user::User,
+    error::{AppError, AppResult},
+    repositories::supabase::SupabaseRestClient,
+    services::{
+        admin_service::AdminService, analytics_service::AnalyticsService, auth_service::AuthService,
+        doctor_service::DoctorService, emergency_access_service::EmergencyAccessService,
+        key_rotation_service::KeyRotationService,
+    },
+};
 
 #[derive(Debug, Default, Clone)]
 pub struct SessionState {
     pub current_user: Option<User>,
+    /// Per-user GoTrue JWT obtained at login, used to scope Supabase REST calls under RLS.
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    /// Holds the password-verified user and GoTrue tokens between `login` and
+    /// `login_verify_totp` for accounts with TOTP enabled, so the second step doesn't need
+    /// the password again.
+    pub pending_totp: Option<PendingTotpLogin>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingTotpLogin {
+    pub user: User,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+/// The long-lived services, each holding its own `SupabaseRestClient` (all built on one shared
+/// pooled `reqwest::Client`), constructed once at startup instead of per command invocation.
+pub struct ServiceRegistry {
+    pub auth: AuthService,
+    pub admin: AdminService,
+    pub doctor: DoctorService,
+    pub analytics: AnalyticsService,
+    pub key_rotation: KeyRotationService,
+    pub emergency_access: EmergencyAccessService,
 }
 
 pub struct AppState {
     pub session: Mutex<SessionState>,
+    pub services: ServiceRegistry,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
+impl AppState {
+    pub fn new() -> AppResult<Self> {
+        // One keep-alive HTTP connection pool shared by every `SupabaseRestClient` below,
+        // instead of each opening (and never reusing a connection with) its own.
+        let http = reqwest::Client::builder().build()?;
+
+        let auth_client = SupabaseRestClient::from_env(http.clone())?;
+        let admin_client = SupabaseRestClient::admin_from_env(http.clone())?;
+        let doctor_client = SupabaseRestClient::from_env(http.clone())?;
+        let analytics_client = SupabaseRestClient::from_env(http.clone())?;
+        let emergency_access_client = SupabaseRestClient::from_env(http)?;
+
+        let doctor = DoctorService::new(doctor_client);
+
+        Ok(Self {
             session: Mutex::new(SessionState::default()),
+            services: ServiceRegistry {
+                auth: AuthService::new(auth_client),
+                key_rotation: KeyRotationService::new(admin_client.clone()),
+                admin: AdminService::new(admin_client),
+                emergency_access: EmergencyAccessService::new(emergency_access_client, doctor.clone()),
+                doctor,
+                analytics: AnalyticsService::new(analytics_client),
+            },
+        })
+    }
+
+    /// Returns a clone of `base` scoped to the logged-in user's GoTrue JWT (if one was
+    /// obtained at login), so PostgREST row-level security is evaluated as that user instead
+    /// of under the shared anon key.
+    pub fn scoped_client(&self, base: &SupabaseRestClient) -> AppResult<SupabaseRestClient> {
+        let session = self
+            .session
+            .lock()
+            .map_err(|_| AppError::Unexpected("failed to lock session".to_string()))?;
+        match session.access_token.clone() {
+            Some(access_token) => Ok(base.with_user_token(access_token, session.refresh_token.clone())),
+            None => Ok(base.clone()),
         }
     }
 }